@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+use crate::decomposition::*;
+use crate::ucd::Ucd;
+
+/// первичные композиции и запечённая таблица композиций
+pub struct Compositions
+{
+    /// (первый, второй) -> композит, без хангыль
+    pairs: BTreeMap<(u32, u32), u32>,
+    /// варианты комбинирования кодпоинта со следующими за ним
+    forward: BTreeMap<u32, u16>,
+    /// варианты комбинирования стартера с предшествующими ему
+    backward: BTreeMap<u32, u16>,
+    /// таблица композиций: второй (или первый) кодпоинт, композит, варианты комбинирования композита
+    pub table: Vec<u64>,
+    /// смещения хеша корзин идеального хеширования пар (группа, второй кодпоинт)
+    pub displacements: Vec<u16>,
+    /// индексы записей таблицы композиций по хешу пары
+    pub slots: Vec<u16>,
+}
+
+impl Compositions
+{
+    /// первичные композиции: канонические декомпозиции из двух кодпоинтов, не исключённые
+    /// из композиции
+    pub fn new(ucd: &Ucd) -> Self
+    {
+        let pairs: BTreeMap<(u32, u32), u32> = ucd
+            .mappings()
+            .filter(|(code, mapping)| {
+                mapping.is_canonical && mapping.codes.len() == 2 && !ucd.is_listed_exclusion(*code)
+            })
+            .map(|(code, mapping)| ((mapping.codes[0], mapping.codes[1]), code))
+            .collect();
+
+        // группы по первому кодпоинту - записи отсортированы по второму
+        let mut forward_groups: BTreeMap<u32, Vec<(u32, u32)>> = BTreeMap::new();
+        // группы по второму кодпоинту - записи отсортированы по первому. используются только группы
+        // стартеров, комбинируемых с предыдущими, но таблица содержит группы всех вторых
+        // кодпоинтов, включая композиции, исключённые лишь как декомпозиции с нестартером в начале
+        let mut backward_groups: BTreeMap<u32, Vec<(u32, u32)>> = BTreeMap::new();
+
+        for (&(first, second), &composite) in pairs.iter() {
+            if !ucd.is_excluded(composite) {
+                forward_groups
+                    .entry(first)
+                    .or_default()
+                    .push((second, composite));
+            }
+
+            backward_groups
+                .entry(second)
+                .or_default()
+                .push((first, composite));
+        }
+
+        for entries in backward_groups.values_mut() {
+            entries.sort();
+        }
+
+        let pairs = pairs
+            .into_iter()
+            .filter(|(_, composite)| !ucd.is_excluded(*composite))
+            .collect();
+
+        // сначала распределяем индексы групп, затем пишем таблицу - записям нужны варианты
+        // комбинирования композитов
+        let mut position = 0;
+
+        let mut combining = |groups: &BTreeMap<u32, Vec<(u32, u32)>>,
+                             is_referenced: &dyn Fn(u32) -> bool|
+         -> BTreeMap<u32, u16> {
+            let mut combining = BTreeMap::new();
+
+            for (&code, entries) in groups.iter() {
+                if is_referenced(code) {
+                    assert!(entries.len() < 0x20 && position <= 0x7FF);
+
+                    combining.insert(code, ((entries.len() as u16) << 11) | position as u16);
+                }
+
+                position += entries.len();
+            }
+
+            combining
+        };
+
+        let forward = combining(&forward_groups, &|_| true);
+        let backward = combining(&backward_groups, &|code| ucd.ccc(code) == 0);
+
+        let mut compositions = Self {
+            pairs,
+            forward,
+            backward,
+            table: vec![],
+            displacements: vec![],
+            slots: vec![],
+        };
+
+        for entries in forward_groups.values().chain(backward_groups.values()) {
+            for &(code, composite) in entries {
+                let combining = compositions.combining(composite) as u64;
+
+                compositions
+                    .table
+                    .push(code as u64 | (composite as u64) << 18 | combining << 48);
+            }
+        }
+
+        compositions.build_hash();
+
+        compositions
+    }
+
+    /// идеальное хеширование (CHD): ключи - пары (начало группы, второй кодпоинт) всех групп,
+    /// на которые ссылаются варианты комбинирования. ключи распределяются по корзинам, для корзин
+    /// по убыванию их размера подбирается смещение, при котором все ключи корзины попадают
+    /// в свободные ячейки
+    fn build_hash(&mut self)
+    {
+        let mut keys = vec![];
+
+        for &combining in self.forward.values().chain(self.backward.values()) {
+            let group = combining & 0x7FF;
+            let count = combining >> 11;
+
+            for index in group .. group + count {
+                keys.push((group, self.table[index as usize] as u32 & 0x3FFFF, index));
+            }
+        }
+
+        let mut buckets = vec![vec![]; keys.len() / 4 + 1];
+        let mut slots = vec![None; keys.len() * 2];
+
+        let bucket_count = buckets.len();
+
+        for &key in &keys {
+            buckets[reduce(composition_hash(key.0, key.1, 0), bucket_count)].push(key);
+        }
+
+        let mut order: Vec<usize> = (0 .. buckets.len()).collect();
+        order.sort_by_key(|&bucket| std::cmp::Reverse(buckets[bucket].len()));
+
+        self.displacements = vec![0; buckets.len()];
+
+        for bucket in order {
+            if buckets[bucket].is_empty() {
+                break;
+            }
+
+            let displacement = (1 ..= u16::MAX)
+                .find(|&displacement| {
+                    let mut taken = vec![];
+
+                    buckets[bucket].iter().all(|&(group, second, _)| {
+                        let slot = reduce(
+                            composition_hash(group, second, displacement as u32),
+                            slots.len(),
+                        );
+
+                        let is_free = slots[slot].is_none() && !taken.contains(&slot);
+                        taken.push(slot);
+
+                        is_free
+                    })
+                })
+                .expect("no displacement for composition hash bucket");
+
+            for &(group, second, index) in &buckets[bucket] {
+                let slot = reduce(
+                    composition_hash(group, second, displacement as u32),
+                    slots.len(),
+                );
+
+                slots[slot] = Some(index);
+            }
+
+            self.displacements[bucket] = displacement;
+        }
+
+        self.slots = slots.into_iter().map(|slot| slot.unwrap_or(0)).collect();
+    }
+
+    /// композиция двух кодпоинтов, включая хангыль
+    pub fn compose(&self, first: u32, second: u32) -> Option<u32>
+    {
+        let l = first.wrapping_sub(HANGUL_L_BASE);
+        let v = second.wrapping_sub(HANGUL_V_BASE);
+
+        if l < HANGUL_L_COUNT && v < HANGUL_V_COUNT {
+            return Some(HANGUL_S_BASE + (l * HANGUL_V_COUNT + v) * HANGUL_T_COUNT);
+        }
+
+        let t = second.wrapping_sub(HANGUL_T_BASE);
+
+        if is_hangul_syllable(first)
+            && (first - HANGUL_S_BASE).is_multiple_of(HANGUL_T_COUNT)
+            && t > 0
+            && t < HANGUL_T_COUNT
+        {
+            return Some(first + t);
+        }
+
+        self.pairs.get(&(first, second)).copied()
+    }
+
+    /// варианты комбинирования кодпоинта со следующими за ним
+    pub fn combining(&self, code: u32) -> u16
+    {
+        self.forward.get(&code).copied().unwrap_or(0)
+    }
+
+    /// варианты комбинирования стартера с предшествующими ему
+    pub fn backward_combining(&self, code: u32) -> u16
+    {
+        self.backward.get(&code).copied().unwrap_or(0)
+    }
+
+    /// кодпоинт может скомбинироваться с нестартером, CCC которого меньше заданного
+    pub fn combines_below(&self, ucd: &Ucd, code: u32, ccc: u8) -> bool
+    {
+        self.pairs
+            .range((code, 0) .. (code + 1, 0))
+            .any(|(&(_, second), _)| (1 .. ccc).contains(&ucd.ccc(second)))
+    }
+}
+
+/// хеш пары (начало группы в таблице композиций, второй кодпоинт), как в нормализаторе
+fn composition_hash(group: u16, second: u32, displacement: u32) -> u32
+{
+    let key = second | (group as u32) << 21;
+
+    (key.wrapping_mul(0x9E3779B1) ^ displacement).wrapping_mul(0x85EBCA6B)
+}
+
+/// индекс в таблице заданной длины по старшим битам хеша
+fn reduce(hash: u32, length: usize) -> usize
+{
+    ((hash as u64 * length as u64) >> 32) as usize
+}
@@ -0,0 +1,67 @@
+use crate::ucd::Ucd;
+
+/// первый слог хангыль
+pub const HANGUL_S_BASE: u32 = 0xAC00;
+/// первая ведущая согласная чамо (L)
+pub const HANGUL_L_BASE: u32 = 0x1100;
+/// первая гласная чамо (V)
+pub const HANGUL_V_BASE: u32 = 0x1161;
+/// T_BASE + 1 - первая замыкающая согласная чамо (T)
+pub const HANGUL_T_BASE: u32 = 0x11A7;
+/// количество ведущих согласных
+pub const HANGUL_L_COUNT: u32 = 19;
+/// количество гласных
+pub const HANGUL_V_COUNT: u32 = 21;
+/// количество замыкающих согласных + 1
+pub const HANGUL_T_COUNT: u32 = 28;
+/// количество слогов с одинаковой ведущей согласной
+pub const HANGUL_N_COUNT: u32 = HANGUL_V_COUNT * HANGUL_T_COUNT;
+/// количество слогов
+pub const HANGUL_S_COUNT: u32 = HANGUL_L_COUNT * HANGUL_N_COUNT;
+
+/// слог хангыль
+pub fn is_hangul_syllable(code: u32) -> bool
+{
+    (HANGUL_S_BASE .. HANGUL_S_BASE + HANGUL_S_COUNT).contains(&code)
+}
+
+/// полная декомпозиция кодпоинта в каноническом порядке. если декомпозиции нет - сам кодпоинт
+pub fn decompose(ucd: &Ucd, code: u32, canonical: bool) -> Vec<u32>
+{
+    let mut result = vec![];
+
+    push_decomposition(ucd, code, canonical, &mut result);
+
+    // нестартеры между стартерами сортируются по CCC, сортировка стабильная
+    for nonstarters in result.split_mut(|&code| ucd.ccc(code) == 0) {
+        nonstarters.sort_by_key(|&code| ucd.ccc(code));
+    }
+
+    result
+}
+
+/// рекурсивная декомпозиция
+fn push_decomposition(ucd: &Ucd, code: u32, canonical: bool, result: &mut Vec<u32>)
+{
+    if is_hangul_syllable(code) {
+        let index = code - HANGUL_S_BASE;
+
+        result.push(HANGUL_L_BASE + index / HANGUL_N_COUNT);
+        result.push(HANGUL_V_BASE + (index % HANGUL_N_COUNT) / HANGUL_T_COUNT);
+
+        if !index.is_multiple_of(HANGUL_T_COUNT) {
+            result.push(HANGUL_T_BASE + index % HANGUL_T_COUNT);
+        }
+
+        return;
+    }
+
+    match ucd.mapping(code) {
+        Some(mapping) if mapping.is_canonical || !canonical => {
+            for &code in mapping.codes.iter() {
+                push_decomposition(ucd, code, canonical, result);
+            }
+        }
+        _ => result.push(code),
+    }
+}
@@ -0,0 +1,481 @@
+use crate::composition::Compositions;
+use crate::decomposition::{decompose, is_hangul_syllable};
+use crate::ucd::{QuickCheck, Ucd};
+
+// маркеры совпадают с MARKER_* из unicode_composing. крейт нормализации не подключается
+// как зависимость: он собирается из запекаемых здесь данных
+
+/// стартер без декомпозиции
+const MARKER_STARTER: u8 = 0b_000;
+/// стартер, комбинируемый с предыдущим кодпоинтом
+const MARKER_COMBINES_BACKWARDS: u8 = 0b_001;
+/// нестартер без декомпозиции
+const MARKER_NONSTARTER: u8 = 0b_010;
+/// синглтон
+const MARKER_SINGLETON: u8 = 0b_011;
+/// декомпозиция во внешнем блоке
+const MARKER_EXPANSION: u8 = 0b_100;
+/// декомпозиция во внешнем блоке с заменой для NF(K)C
+const MARKER_EXPANSION_COMBINED_PATCH: u8 = 0b_101;
+/// декомпозиция во внешнем блоке, в NF(K)C кодпоинт не декомпозируется
+const MARKER_EXPANSION_COMBINED_EMPTY: u8 = 0b_110;
+/// слог хангыль
+const MARKER_HANGUL_SYLLABLE: u8 = 0b_111;
+
+/// кодпоинты до этого (включительно) хранятся в непрерывной части таблицы
+pub const CONTINUOUS_BLOCK_END: u32 = 0x0FFF;
+/// размер индекса блоков, за ним следуют группы индексов блоков данных
+const INDEX_GROUPS_BASE: usize = 0x600;
+/// количество кодпоинтов Unicode
+const CODEPOINTS: u32 = 0x110000;
+
+/// таблицы данных декомпозиции (DecompositionData)
+pub struct DecompositionTables
+{
+    pub index: Vec<u16>,
+    pub data: Vec<u32>,
+    pub expansions: Vec<u32>,
+    pub quick_check: Vec<u64>,
+    pub quick_check_index: Vec<u16>,
+    pub quick_check_rows: Vec<u64>,
+    pub continuous_block_end: u32,
+}
+
+/// все запечённые данные
+pub struct Baked
+{
+    pub unicode_version: (u8, u8, u8),
+    pub nfd: DecompositionTables,
+    pub nfkd: DecompositionTables,
+    pub compositions: Vec<u64>,
+    pub composition_displacements: Vec<u16>,
+    pub composition_slots: Vec<u16>,
+    pub nfc_expansions: Vec<u32>,
+    pub nfkc_expansions: Vec<u32>,
+    pub last_decomposing_codepoint: u32,
+}
+
+/// запечь данные нормализации
+pub fn bake(ucd: &Ucd) -> Baked
+{
+    let compositions = Compositions::new(ucd);
+
+    let (nfd, nfd_expansions, nfc_expansions) = Encoder::new(ucd, &compositions, true).encode();
+    let (nfkd, nfkd_expansions, nfkc_expansions) = Encoder::new(ucd, &compositions, false).encode();
+
+    let last_decomposing_codepoint = (0 .. CODEPOINTS)
+        .rev()
+        .find(|&code| nfd[code as usize] != 0 || nfkd[code as usize] != 0)
+        .unwrap_or(0);
+
+    let last_block = last_decomposing_codepoint >> 7;
+
+    assert!(last_block < INDEX_GROUPS_BASE as u32);
+
+    Baked {
+        unicode_version: ucd.version,
+        nfd: layout(&nfd, nfd_expansions, last_block),
+        nfkd: layout(&nfkd, nfkd_expansions, last_block),
+        compositions: compositions.table,
+        composition_displacements: compositions.displacements,
+        composition_slots: compositions.slots,
+        nfc_expansions,
+        nfkc_expansions,
+        last_decomposing_codepoint,
+    }
+}
+
+/// кодирование значений кодпоинтов для одной формы нормализации
+struct Encoder<'a>
+{
+    ucd: &'a Ucd,
+    compositions: &'a Compositions,
+    canonical: bool,
+    /// CCC -> порядковый номер среди встречающихся значений CCC
+    ranks: [u8; 256],
+    /// таблица расширений
+    expansions: Vec<u32>,
+    /// таблица замены расширений NF(K)C
+    patch: Vec<u32>,
+}
+
+impl<'a> Encoder<'a>
+{
+    fn new(ucd: &'a Ucd, compositions: &'a Compositions, canonical: bool) -> Self
+    {
+        let mut ranks = [0; 256];
+
+        for (rank, ccc) in ucd.ccc_values().into_iter().enumerate() {
+            ranks[ccc as usize] = rank as u8;
+        }
+
+        Self {
+            ucd,
+            compositions,
+            canonical,
+            ranks,
+            expansions: vec![],
+            patch: vec![],
+        }
+    }
+
+    /// значения всех кодпоинтов, таблица расширений и таблица замены расширений
+    fn encode(mut self) -> (Vec<u32>, Vec<u32>, Vec<u32>)
+    {
+        let values = (0 .. CODEPOINTS).map(|code| self.value(code)).collect();
+
+        (values, self.expansions, self.patch)
+    }
+
+    /// значение кодпоинта
+    fn value(&mut self, code: u32) -> u32
+    {
+        let ccc = self.ucd.ccc(code);
+        let quick_check =
+            (self.ucd.quick_check(code, self.canonical) != QuickCheck::Yes || ccc != 0) as u32;
+
+        if is_hangul_syllable(code) {
+            return (MARKER_HANGUL_SYLLABLE as u32) << 1;
+        }
+
+        let decomposition = decompose(self.ucd, code, self.canonical);
+
+        if decomposition == [code] {
+            return match ccc {
+                0 => match self.ucd.quick_check(code, self.canonical) {
+                    QuickCheck::Maybe => {
+                        (self.compositions.backward_combining(code) as u32) << 16
+                            | (MARKER_COMBINES_BACKWARDS as u32) << 1
+                            | quick_check
+                    }
+                    _ => {
+                        (self.compositions.combining(code) as u32) << 16
+                            | (MARKER_STARTER as u32) << 1
+                            | quick_check
+                    }
+                },
+                // бит 16 - нестартер может скомбинироваться с предыдущим кодпоинтом (NF(K)C_QC = Maybe)
+                _ => {
+                    let maybe =
+                        (self.ucd.quick_check(code, self.canonical) != QuickCheck::Yes) as u32;
+
+                    maybe << 16
+                        | (self.rank(code) as u32) << 8
+                        | (MARKER_NONSTARTER as u32) << 1
+                        | quick_check
+                }
+            };
+        }
+
+        let first_ccc = self.ucd.ccc(decomposition[0]);
+
+        // синглтон
+        if decomposition.len() == 1 && first_ccc == 0 {
+            return decomposition[0] << 8 | (MARKER_SINGLETON as u32) << 1 | quick_check;
+        }
+
+        // пара стартер + нестартер записывается в значение как есть, если младшие 7 бит стартера,
+        // попадающие на место маркера, не совпадают ни с одним из маркеров. иначе - расширение
+        if decomposition.len() == 2
+            && first_ccc == 0
+            && self.ucd.ccc(decomposition[1]) != 0
+            && decomposition[0] < 0x8000
+            && decomposition[1] < 0x10000
+        {
+            return match decomposition[0] & 0x7F >= 8 {
+                true => decomposition[1] << 16 | decomposition[0] << 1 | quick_check,
+                false => self.expansion(&decomposition, quick_check),
+            };
+        }
+
+        // декомпозиция начинается с нестартера - в NF(K)C кодпоинт декомпозируется как есть
+        if first_ccc != 0 {
+            return self.expansion(&decomposition, quick_check);
+        }
+
+        let precomposed = self.precompose(&decomposition);
+
+        if precomposed == [code] {
+            let info = self.compositions.combining(code) as u32;
+
+            return self.combined_expansion(
+                &decomposition,
+                info,
+                MARKER_EXPANSION_COMBINED_EMPTY,
+                quick_check,
+            );
+        }
+
+        if precomposed == decomposition {
+            return self.expansion(&decomposition, quick_check);
+        }
+
+        // замена: последний стартер, количество и индекс кодпоинтов прекомпозиции,
+        // варианты комбинирования последнего стартера
+        let last_starter = self.last_starter(&precomposed);
+        let entries: Vec<u32> = precomposed.iter().map(|&code| self.entry(code)).collect();
+        let index = self.patch.len() as u32;
+
+        assert!(entries.len() < 8 && index <= 0x3FF);
+
+        self.patch.extend(entries);
+
+        let info = last_starter
+            | (precomposed.len() as u32) << 3
+            | index << 6
+            | (self
+                .compositions
+                .combining(precomposed[last_starter as usize]) as u32)
+                << 16;
+
+        self.combined_expansion(
+            &decomposition,
+            info,
+            MARKER_EXPANSION_COMBINED_PATCH,
+            quick_check,
+        )
+    }
+
+    /// декомпозиция во внешнем блоке, совпадающие последовательности переиспользуются
+    fn expansion(&mut self, decomposition: &[u32], quick_check: u32) -> u32
+    {
+        let entries: Vec<u32> = decomposition.iter().map(|&code| self.entry(code)).collect();
+
+        let index = match self
+            .expansions
+            .windows(entries.len())
+            .position(|window| window == entries)
+        {
+            Some(index) => index,
+            None => {
+                self.expansions.extend(&entries);
+                self.expansions.len() - entries.len()
+            }
+        };
+
+        self.expansion_value(decomposition, index, MARKER_EXPANSION, quick_check)
+    }
+
+    /// декомпозиция во внешнем блоке, перед которой записана информация для NF(K)C
+    fn combined_expansion(
+        &mut self,
+        decomposition: &[u32],
+        info: u32,
+        marker: u8,
+        quick_check: u32,
+    ) -> u32
+    {
+        let index = self.expansions.len();
+
+        self.expansions.push(info);
+
+        for &code in decomposition {
+            let entry = self.entry(code);
+            self.expansions.push(entry);
+        }
+
+        self.expansion_value(decomposition, index, marker, quick_check)
+    }
+
+    /// значение кодпоинта с декомпозицией во внешнем блоке
+    fn expansion_value(
+        &self,
+        decomposition: &[u32],
+        index: usize,
+        marker: u8,
+        quick_check: u32,
+    ) -> u32
+    {
+        assert!(decomposition.len() < 0x20 && index < 0x4000);
+
+        (index as u32) << 18
+            | (decomposition.len() as u32) << 13
+            | self.last_starter(decomposition) << 8
+            | (marker as u32) << 1
+            | quick_check
+    }
+
+    /// прекомпозиция декомпозиции для NF(K)C. стартеры комбинируются как при обычной композиции,
+    /// но последний стартер комбинируется с нестартерами, следующими за ним, только пока нестартер
+    /// с меньшим CCC, оказавшийся после декомпозиции в тексте, не смог бы скомбинироваться
+    /// с полученным композитом - иначе такой нестартер должен скомбинироваться первым
+    fn precompose(&self, decomposition: &[u32]) -> Vec<u32>
+    {
+        let last_starter = self.last_starter(decomposition) as usize;
+
+        let mut result = vec![decomposition[0]];
+        let mut starter = 0;
+        let mut previous_ccc = 0;
+        let mut stopped = false;
+
+        for (i, &code) in decomposition.iter().enumerate().skip(1) {
+            let ccc = self.ucd.ccc(code);
+            let trailing = i > last_starter;
+            let blocked = result.len() - 1 != starter && previous_ccc >= ccc;
+
+            let composite = match stopped || blocked {
+                true => None,
+                false => self.compositions.compose(result[starter], code),
+            }
+            .filter(|_| {
+                !trailing
+                    || !self
+                        .compositions
+                        .combines_below(self.ucd, result[starter], ccc)
+            });
+
+            match composite {
+                Some(composite) => result[starter] = composite,
+                None => {
+                    stopped |= trailing;
+
+                    if ccc == 0 {
+                        starter = result.len();
+                    }
+
+                    previous_ccc = ccc;
+                    result.push(code);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// индекс последнего стартера последовательности (0, если стартеров нет)
+    fn last_starter(&self, codes: &[u32]) -> u32
+    {
+        codes
+            .iter()
+            .rposition(|&code| self.ucd.ccc(code) == 0)
+            .unwrap_or(0) as u32
+    }
+
+    /// элемент расширения: кодпоинт и порядковый номер его CCC
+    fn entry(&self, code: u32) -> u32
+    {
+        code << 8 | self.rank(code) as u32
+    }
+
+    /// порядковый номер CCC кодпоинта
+    fn rank(&self, code: u32) -> u8
+    {
+        self.ranks[self.ucd.ccc(code) as usize]
+    }
+}
+
+/// раскладка значений по блокам: 128 кодпоинтов блока - 16 блоков данных по 8 значений.
+/// одинаковые блоки данных и одинаковые группы индексов переиспользуются, кроме групп
+/// непрерывной части таблицы - они всегда идут подряд с начала групп индексов
+fn layout(values: &[u32], expansions: Vec<u32>, last_block: u32) -> DecompositionTables
+{
+    let mut index = vec![0u16; INDEX_GROUPS_BASE];
+    let mut data: Vec<u32> = vec![];
+
+    for block in 0 ..= last_block {
+        let group: Vec<u16> = values[(block << 7) as usize ..][.. 0x80]
+            .chunks(8)
+            .map(|chunk| {
+                let position = match data.chunks(8).position(|existing| existing == chunk) {
+                    Some(position) => position * 8,
+                    None => {
+                        data.extend(chunk);
+                        data.len() - 8
+                    }
+                };
+
+                assert!(position <= 0xFFFF);
+
+                position as u16
+            })
+            .collect();
+
+        let existing = match block <= CONTINUOUS_BLOCK_END >> 7 {
+            true => None,
+            false => index[INDEX_GROUPS_BASE ..]
+                .chunks(16)
+                .position(|existing| existing == group)
+                .map(|position| INDEX_GROUPS_BASE + position * 16),
+        };
+
+        index[block as usize] = match existing {
+            Some(position) => position,
+            None => {
+                index.extend(&group);
+                index.len() - 16
+            }
+        } as u16;
+    }
+
+    let (quick_check_index, quick_check_rows) = quick_check_rows(values);
+
+    DecompositionTables {
+        index,
+        data,
+        expansions,
+        quick_check: quick_check(values),
+        quick_check_index,
+        quick_check_rows,
+        continuous_block_end: CONTINUOUS_BLOCK_END,
+    }
+}
+
+/// таблицы быстрой проверки последовательностей UTF-8 по первым двум байтам: 64 элемента для NF(K)C -
+/// у кодпоинтов не установлен бит проверки, затем 64 для NF(K)D - кодпоинты являются стартерами
+/// без декомпозиции. элемент - младшие 6 бит первого байта, бит - младшие 6 бит второго:
+/// элементы 0 .. 32 - отдельные кодпоинты U+0080 ..= U+07FF, 32 .. 48 - блоки по 64 кодпоинта
+/// U+0800 ..= U+FFFF, проходящие проверку целиком, 48 .. 64 (4-байтовые последовательности) пусты
+fn quick_check(values: &[u32]) -> Vec<u64>
+{
+    let mut table = vec![0; 128];
+
+    for (half, mask) in [(0, 1), (64, 0xFC)] {
+        for code in 0x80 .. 0x800 {
+            if values[code] & mask == 0 {
+                table[half + (code >> 6)] |= 1 << (code & 0x3F);
+            }
+        }
+
+        for block in 0x800 >> 6 .. 0x10000 >> 6 {
+            if values[block << 6 ..][.. 64]
+                .iter()
+                .all(|value| value & mask == 0)
+            {
+                table[half + 32 + (block >> 6)] |= 1 << (block & 0x3F);
+            }
+        }
+    }
+
+    table
+}
+
+/// быстрая проверка отдельных 3-байтовых последовательностей: элемент индекса - младшие 4 бита первого
+/// байта и младшие 6 бит второго (кодпоинт >> 6; 1024 элемента для NF(K)C, затем 1024 для NF(K)D) -
+/// номер строки, бит строки - младшие 6 бит третьего байта. одинаковые строки хранятся один раз
+fn quick_check_rows(values: &[u32]) -> (Vec<u16>, Vec<u64>)
+{
+    let mut index = vec![];
+    let mut rows = vec![];
+
+    for mask in [1, 0xFC] {
+        for prefix in 0 .. 0x10000 >> 6 {
+            let row = values[prefix << 6 ..][.. 64]
+                .iter()
+                .enumerate()
+                .filter(|(_, &value)| value & mask == 0)
+                .fold(0u64, |row, (bit, _)| row | 1 << bit);
+
+            let position = match rows.iter().position(|&existing| existing == row) {
+                Some(position) => position,
+                None => {
+                    rows.push(row);
+                    rows.len() - 1
+                }
+            };
+
+            index.push(position as u16);
+        }
+    }
+
+    (index, rows)
+}
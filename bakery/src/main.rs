@@ -0,0 +1,45 @@
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+
+mod composition;
+mod decomposition;
+mod encode;
+mod output;
+mod ucd;
+
+/// запекание таблиц нормализации из файлов UCD:
+/// UnicodeData.txt, CompositionExclusions.txt и DerivedNormalizationProps.txt
+fn main() -> ExitCode
+{
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 2 {
+        eprintln!(
+            "Использование: {} <каталог UCD> [<каталог данных, по умолчанию ./../data>]",
+            args[0]
+        );
+        return ExitCode::FAILURE;
+    }
+
+    let ucd_dir = Path::new(&args[1]);
+    let data_dir = Path::new(args.get(2).map(String::as_str).unwrap_or("./../data"));
+
+    let ucd = match ucd::Ucd::read(ucd_dir) {
+        Ok(ucd) => ucd,
+        Err(error) => {
+            eprintln!("Не удалось прочитать UCD: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let baked = encode::bake(&ucd);
+
+    match output::write(&baked, data_dir) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Не удалось записать данные: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
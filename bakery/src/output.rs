@@ -0,0 +1,102 @@
+use std::fmt::UpperHex;
+use std::fs;
+use std::path::Path;
+
+use crate::encode::{Baked, DecompositionTables};
+
+/// максимальная ширина строки массива
+const LINE_WIDTH: usize = 124;
+
+/// записать запечённые данные в подкаталог версии Unicode (например, 15.1.0)
+pub fn write(baked: &Baked, dir: &Path) -> Result<(), String>
+{
+    let (major, minor, update) = baked.unicode_version;
+    let version = format!("UnicodeVersion::new({}, {}, {})", major, minor, update);
+
+    let dir = dir.join(format!("{}.{}.{}", major, minor, update));
+
+    fs::create_dir_all(&dir).map_err(|error| format!("{}: {}", dir.display(), error))?;
+
+    let files = [
+        ("nfd.txt", decomposition_data(&baked.nfd, &version)),
+        ("nfkd.txt", decomposition_data(&baked.nfkd, &version)),
+        ("compositions.txt", composition_data(baked)),
+        ("nfc.txt", expansions_patch(&baked.nfc_expansions)),
+        ("nfkc.txt", expansions_patch(&baked.nfkc_expansions)),
+        (
+            "last_decomposing_codepoint.txt",
+            format!("0x{:X}\n", baked.last_decomposing_codepoint),
+        ),
+    ];
+
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents).map_err(|error| format!("{}: {}", name, error))?;
+    }
+
+    Ok(())
+}
+
+/// DecompositionData
+fn decomposition_data(tables: &DecompositionTables, version: &str) -> String
+{
+    format!(
+        "DecompositionData {{\n{}{}{}{}{}{}  continuous_block_end: 0x{:04X},\n  unicode_version: {},\n}}\n",
+        field("index", &tables.index),
+        field("data", &tables.data),
+        field("expansions", &tables.expansions),
+        field("quick_check", &tables.quick_check),
+        field("quick_check_index", &tables.quick_check_index),
+        field("quick_check_rows", &tables.quick_check_rows),
+        tables.continuous_block_end,
+        version
+    )
+}
+
+/// CompositionData
+fn composition_data(baked: &Baked) -> String
+{
+    format!(
+        "CompositionData {{\n{}{}{}}}\n",
+        field("compositions", &baked.compositions),
+        field("displacements", &baked.composition_displacements),
+        field("slots", &baked.composition_slots)
+    )
+}
+
+/// ExpansionsPatch
+fn expansions_patch(expansions: &[u32]) -> String
+{
+    format!(
+        "ExpansionsPatch {{\n{}}}\n",
+        field("expansions", expansions)
+    )
+}
+
+/// поле-срез: значения в шестнадцатеричной записи, строки не длиннее LINE_WIDTH
+fn field<T: UpperHex + Default + PartialEq>(name: &str, values: &[T]) -> String
+{
+    let mut result = format!("  {}: &[\n", name);
+    let mut line = String::new();
+
+    for value in values {
+        let item = match *value == T::default() {
+            true => "0, ".to_owned(),
+            false => format!("0x{:X}, ", value),
+        };
+
+        if !line.is_empty() && 4 + line.len() + item.len() > LINE_WIDTH {
+            result.push_str(&format!("    {}\n", line));
+            line.clear();
+        }
+
+        line.push_str(&item);
+    }
+
+    if !line.is_empty() {
+        result.push_str(&format!("    {}\n", line));
+    }
+
+    result.push_str("  ],\n");
+
+    result
+}
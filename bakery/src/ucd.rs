@@ -0,0 +1,280 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// декомпозиция кодпоинта из UnicodeData.txt (один шаг, без рекурсии)
+pub struct Mapping
+{
+    /// каноническая декомпозиция (без тега совместимости)
+    pub is_canonical: bool,
+    /// кодпоинты декомпозиции
+    pub codes: Vec<u32>,
+}
+
+/// значение свойств NFC_QC / NFKC_QC
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum QuickCheck
+{
+    Yes,
+    No,
+    Maybe,
+}
+
+/// данные UCD, необходимые для запекания таблиц нормализации
+pub struct Ucd
+{
+    /// версия Unicode (major, minor, update)
+    pub version: (u8, u8, u8),
+    /// классы канонического комбинирования (CCC)
+    ccc: BTreeMap<u32, u8>,
+    /// декомпозиции
+    mappings: BTreeMap<u32, Mapping>,
+    /// исключения композиции из CompositionExclusions.txt
+    exclusions: BTreeSet<u32>,
+    /// полные исключения композиции (Full_Composition_Exclusion)
+    full_exclusions: BTreeSet<u32>,
+    /// NFC_QC, отличные от Yes
+    nfc_qc: BTreeMap<u32, QuickCheck>,
+    /// NFKC_QC, отличные от Yes
+    nfkc_qc: BTreeMap<u32, QuickCheck>,
+}
+
+impl Ucd
+{
+    /// прочитать UnicodeData.txt, CompositionExclusions.txt и DerivedNormalizationProps.txt
+    /// из каталога
+    pub fn read(dir: &Path) -> Result<Self, String>
+    {
+        let properties = read_file(dir, "DerivedNormalizationProps.txt")?;
+
+        let mut ucd = Self {
+            version: parse_version(&properties)?,
+            ccc: BTreeMap::new(),
+            mappings: BTreeMap::new(),
+            exclusions: BTreeSet::new(),
+            full_exclusions: BTreeSet::new(),
+            nfc_qc: BTreeMap::new(),
+            nfkc_qc: BTreeMap::new(),
+        };
+
+        ucd.read_unicode_data(&read_file(dir, "UnicodeData.txt")?)?;
+        ucd.read_exclusions(&read_file(dir, "CompositionExclusions.txt")?)?;
+        ucd.read_properties(&properties)?;
+
+        Ok(ucd)
+    }
+
+    /// CCC кодпоинта
+    pub fn ccc(&self, code: u32) -> u8
+    {
+        self.ccc.get(&code).copied().unwrap_or(0)
+    }
+
+    /// все встречающиеся значения CCC, по возрастанию
+    pub fn ccc_values(&self) -> BTreeSet<u8>
+    {
+        let mut values: BTreeSet<u8> = self.ccc.values().copied().collect();
+        values.insert(0);
+
+        values
+    }
+
+    /// декомпозиция кодпоинта из UnicodeData.txt
+    pub fn mapping(&self, code: u32) -> Option<&Mapping>
+    {
+        self.mappings.get(&code)
+    }
+
+    /// все декомпозиции, по возрастанию кодпоинтов
+    pub fn mappings(&self) -> impl Iterator<Item = (u32, &Mapping)>
+    {
+        self.mappings.iter().map(|(&code, mapping)| (code, mapping))
+    }
+
+    /// кодпоинт исключён из композиции (Full_Composition_Exclusion)
+    pub fn is_excluded(&self, code: u32) -> bool
+    {
+        self.exclusions.contains(&code) || self.full_exclusions.contains(&code)
+    }
+
+    /// кодпоинт перечислен в CompositionExclusions.txt. в отличие от Full_Composition_Exclusion,
+    /// не включает синглтоны и декомпозиции, начинающиеся с нестартера
+    pub fn is_listed_exclusion(&self, code: u32) -> bool
+    {
+        self.exclusions.contains(&code)
+    }
+
+    /// значение NFC_QC / NFKC_QC кодпоинта
+    pub fn quick_check(&self, code: u32, canonical: bool) -> QuickCheck
+    {
+        let properties = match canonical {
+            true => &self.nfc_qc,
+            false => &self.nfkc_qc,
+        };
+
+        properties.get(&code).copied().unwrap_or(QuickCheck::Yes)
+    }
+
+    /// UnicodeData.txt: 0 - кодпоинт, 3 - CCC, 5 - декомпозиция.
+    /// диапазоны (<..., First> / <..., Last>) не имеют ни CCC, ни декомпозиций - пропускаем их
+    fn read_unicode_data(&mut self, text: &str) -> Result<(), String>
+    {
+        for (line_number, line) in lines(text) {
+            let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+
+            if fields.len() < 6 {
+                return Err(format!("UnicodeData.txt:{}: неверный формат", line_number));
+            }
+
+            let code = parse_code(fields[0], line_number)?;
+            let ccc: u8 = fields[3]
+                .parse()
+                .map_err(|_| format!("UnicodeData.txt:{}: неверный CCC", line_number))?;
+
+            if ccc != 0 {
+                self.ccc.insert(code, ccc);
+            }
+
+            if fields[5].is_empty() {
+                continue;
+            }
+
+            let (is_canonical, codes) = match fields[5].strip_prefix('<') {
+                Some(tagged) => match tagged.split_once('>') {
+                    Some((_, codes)) => (false, codes),
+                    None => return Err(format!("UnicodeData.txt:{}: неверный тег", line_number)),
+                },
+                None => (true, fields[5]),
+            };
+
+            let codes = codes
+                .split_whitespace()
+                .map(|code| parse_code(code, line_number))
+                .collect::<Result<Vec<u32>, String>>()?;
+
+            self.mappings.insert(
+                code,
+                Mapping {
+                    is_canonical,
+                    codes,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    /// CompositionExclusions.txt: кодпоинт или диапазон в первом поле
+    fn read_exclusions(&mut self, text: &str) -> Result<(), String>
+    {
+        for (line_number, line) in lines(text) {
+            let field = line.split(';').next().unwrap_or_default();
+
+            self.exclusions.extend(parse_range(field, line_number)?);
+        }
+
+        Ok(())
+    }
+
+    /// DerivedNormalizationProps.txt: Full_Composition_Exclusion, NFC_QC и NFKC_QC,
+    /// остальные свойства не используются
+    fn read_properties(&mut self, text: &str) -> Result<(), String>
+    {
+        for (line_number, line) in lines(text) {
+            let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+
+            let range = parse_range(fields[0], line_number)?;
+
+            match fields.get(1 ..) {
+                Some(["Full_Composition_Exclusion"]) => self.full_exclusions.extend(range),
+                Some([property @ ("NFC_QC" | "NFKC_QC"), value]) => {
+                    let value = match *value {
+                        "N" => QuickCheck::No,
+                        "M" => QuickCheck::Maybe,
+                        "Y" => QuickCheck::Yes,
+                        _ => {
+                            return Err(format!(
+                                "DerivedNormalizationProps.txt:{}: неверное значение {}",
+                                line_number, property
+                            ))
+                        }
+                    };
+
+                    let properties = match *property == "NFC_QC" {
+                        true => &mut self.nfc_qc,
+                        false => &mut self.nfkc_qc,
+                    };
+
+                    properties.extend(range.map(|code| (code, value)));
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// прочитать файл UCD
+fn read_file(dir: &Path, name: &str) -> Result<String, String>
+{
+    fs::read_to_string(dir.join(name)).map_err(|error| format!("{}: {}", name, error))
+}
+
+/// версия Unicode из заголовка файла UCD: # DerivedNormalizationProps-15.1.0.txt
+fn parse_version(text: &str) -> Result<(u8, u8, u8), String>
+{
+    let version = text
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("# DerivedNormalizationProps-"))
+        .and_then(|line| line.trim().strip_suffix(".txt"))
+        .map(|version| {
+            version
+                .split('.')
+                .map(str::parse)
+                .collect::<Vec<Result<u8, _>>>()
+        });
+
+    match version.as_deref() {
+        Some([Ok(major), Ok(minor), Ok(update)]) => Ok((*major, *minor, *update)),
+        _ => Err("DerivedNormalizationProps.txt: не удалось определить версию Unicode".to_owned()),
+    }
+}
+
+/// непустые строки файла UCD без комментариев, с номерами строк
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)>
+{
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.split('#').next().unwrap_or_default().trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+/// кодпоинт в шестнадцатеричной записи
+fn parse_code(source: &str, line_number: usize) -> Result<u32, String>
+{
+    u32::from_str_radix(source.trim(), 16)
+        .ok()
+        .filter(|&code| code <= 0x10FFFF)
+        .ok_or(format!(
+            "строка {}: неверный кодпоинт {}",
+            line_number, source
+        ))
+}
+
+/// кодпоинт или диапазон кодпоинтов вида XXXX..YYYY
+fn parse_range(source: &str, line_number: usize) -> Result<RangeInclusive<u32>, String>
+{
+    match source.trim().split_once("..") {
+        Some((first, last)) => {
+            Ok(parse_code(first, line_number)? ..= parse_code(last, line_number)?)
+        }
+        None => {
+            let code = parse_code(source, line_number)?;
+
+            Ok(code ..= code)
+        }
+    }
+}
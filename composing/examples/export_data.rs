@@ -0,0 +1,17 @@
+//! запись запечённых данных нормализации в бинарный формат:
+//! cargo run --example export_data -- normalization.bin
+
+use unicode_composing::NormalizationData;
+
+fn main()
+{
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or(String::from("normalization.bin"));
+
+    let bytes = NormalizationData::baked().to_bytes();
+
+    std::fs::write(&path, &bytes).unwrap();
+
+    println!("{}: {} bytes", path, bytes.len());
+}
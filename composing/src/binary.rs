@@ -0,0 +1,416 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::data::{CompositionData, DecompositionData, ExpansionsPatch, NormalizationData};
+use crate::version::UnicodeVersion;
+use crate::{ComposingNormalizer, DecomposingNormalizer};
+
+// бинарный формат данных нормализации. все числа - little-endian.
+//
+// заголовок (96 байт):
+//   0  магическое число MAGIC
+//   8  версия формата (u16), 2 байта зарезервировано
+//  12  версия Unicode: major, minor, update (u8), 1 байт зарезервирован
+//  16  continuous_block_end NFD и NFKD (u32)
+//  24  количество элементов каждой из 17 таблиц (u32): индекс, данные, расширения, таблица, индекс
+//      и строки быстрой проверки NFD, то же для NFKD, композиции, смещения и ячейки хеша композиций,
+//      замена расширений NFC и NFKC
+//  92  контрольная сумма FNV-1a (u32) всех данных, следующих за заголовком
+//
+// таблицы следуют за заголовком в том же порядке, каждая начинается со смещения, кратного 16 байтам
+
+/// магическое число бинарных данных
+pub const MAGIC: [u8; 8] = *b"UNORMDAT";
+/// версия бинарного формата
+pub const FORMAT_VERSION: u16 = 2;
+
+/// размер заголовка
+const HEADER_SIZE: usize = 96;
+/// выравнивание таблиц
+const TABLE_ALIGN: usize = 16;
+/// количество таблиц
+const TABLES: usize = 17;
+/// размеры элементов таблиц в порядке их следования
+const TABLE_ELEMENT_SIZES: [usize; TABLES] = [2, 4, 4, 8, 2, 8, 2, 4, 4, 8, 2, 8, 8, 2, 2, 4, 4];
+
+/// ошибка загрузки данных нормализации
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataError
+{
+    /// размер данных не соответствует заголовку
+    InvalidLength
+    {
+        expected: usize, actual: usize
+    },
+    /// неизвестное магическое число
+    InvalidMagic,
+    /// неподдерживаемая версия формата
+    UnsupportedFormatVersion(u16),
+    /// форма нормализации отключена возможностями крейта
+    FormDisabled(&'static str),
+    /// в данных нет таблиц формы нормализации (форма была отключена при экспорте)
+    MissingForm(&'static str),
+    /// данные этой версии Unicode не запечены (или не подключены возможностью крейта)
+    UnsupportedUnicodeVersion(UnicodeVersion),
+    /// контрольная сумма не совпадает
+    ChecksumMismatch
+    {
+        expected: u32, actual: u32
+    },
+    /// данные не выровнены по 8 байтам
+    Misaligned,
+    /// загрузка без копирования возможна только на little-endian платформах
+    UnsupportedEndianness,
+    /// данные кодпоинта нарушают ограничения формата
+    InvalidCodepoint
+    {
+        code: u32, reason: &'static str
+    },
+    /// элемент таблицы композиций нарушает ограничения формата
+    InvalidComposition
+    {
+        index: usize, reason: &'static str
+    },
+}
+
+impl fmt::Display for DataError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            Self::InvalidLength { expected, actual } => write!(
+                f,
+                "invalid normalization data length: {} bytes expected, {} found",
+                expected, actual
+            ),
+            Self::InvalidMagic => write!(f, "invalid normalization data magic"),
+            Self::UnsupportedFormatVersion(version) => {
+                write!(
+                    f,
+                    "unsupported normalization data format version {}",
+                    version
+                )
+            }
+            Self::FormDisabled(form) => {
+                write!(f, "{} normalization is disabled by crate features", form)
+            }
+            Self::MissingForm(form) => {
+                write!(f, "normalization data contains no {} tables", form)
+            }
+            Self::UnsupportedUnicodeVersion(version) => {
+                write!(f, "no baked normalization data for Unicode {}", version)
+            }
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "normalization data checksum mismatch: {:#010X} expected, {:#010X} found",
+                expected, actual
+            ),
+            Self::Misaligned => write!(f, "normalization data is not aligned to 8 bytes"),
+            Self::UnsupportedEndianness => {
+                write!(
+                    f,
+                    "normalization data can only be loaded on little-endian targets"
+                )
+            }
+            Self::InvalidCodepoint { code, reason } => {
+                write!(
+                    f,
+                    "invalid normalization data for U+{:04X}: {}",
+                    code, reason
+                )
+            }
+            Self::InvalidComposition { index, reason } => {
+                write!(f, "invalid composition table entry {}: {}", index, reason)
+            }
+        }
+    }
+}
+
+impl core::error::Error for DataError {}
+
+impl<'a> NormalizationData<'a>
+{
+    /// загрузить данные из бинарного формата без копирования (например, из отображённого в память файла).
+    /// данные должны быть выровнены по 8 байтам, их целостность проверяется (см. validate).
+    /// данные, экспортированные сборкой без некоторых форм, не содержат таблиц этих форм
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, DataError>
+    {
+        if cfg!(target_endian = "big") {
+            return Err(DataError::UnsupportedEndianness);
+        }
+
+        if bytes.len() < HEADER_SIZE {
+            return Err(DataError::InvalidLength {
+                expected: HEADER_SIZE,
+                actual: bytes.len(),
+            });
+        }
+
+        if bytes[.. 8] != MAGIC {
+            return Err(DataError::InvalidMagic);
+        }
+
+        let format_version = u16::from_le_bytes([bytes[8], bytes[9]]);
+
+        if format_version != FORMAT_VERSION {
+            return Err(DataError::UnsupportedFormatVersion(format_version));
+        }
+
+        if !(bytes.as_ptr() as usize).is_multiple_of(8) {
+            return Err(DataError::Misaligned);
+        }
+
+        // размеры таблиц в байтах
+        let mut sizes = [0; TABLES];
+
+        for (i, size) in sizes.iter_mut().enumerate() {
+            *size = (read_u32(bytes, 24 + i * 4) as usize).saturating_mul(TABLE_ELEMENT_SIZES[i]);
+        }
+
+        let expected = blob_size(&sizes);
+
+        if bytes.len() != expected {
+            return Err(DataError::InvalidLength {
+                expected,
+                actual: bytes.len(),
+            });
+        }
+
+        let checksum = read_u32(bytes, 92);
+        let actual = fnv1a(&bytes[HEADER_SIZE ..]);
+
+        if checksum != actual {
+            return Err(DataError::ChecksumMismatch {
+                expected: checksum,
+                actual,
+            });
+        }
+
+        let mut tables = Tables {
+            bytes,
+            offset: HEADER_SIZE,
+            sizes,
+            table: 0,
+        };
+
+        let unicode_version = UnicodeVersion::new(bytes[12], bytes[13], bytes[14]);
+
+        let data = Self {
+            unicode_version,
+            nfd: DecompositionData {
+                index: tables.next(),
+                data: tables.next(),
+                expansions: tables.next(),
+                quick_check: tables.next(),
+                quick_check_index: tables.next(),
+                quick_check_rows: tables.next(),
+                continuous_block_end: read_u32(bytes, 16),
+                unicode_version,
+            },
+            nfkd: DecompositionData {
+                index: tables.next(),
+                data: tables.next(),
+                expansions: tables.next(),
+                quick_check: tables.next(),
+                quick_check_index: tables.next(),
+                quick_check_rows: tables.next(),
+                continuous_block_end: read_u32(bytes, 20),
+                unicode_version,
+            },
+            compositions: CompositionData {
+                compositions: tables.next(),
+                displacements: tables.next(),
+                slots: tables.next(),
+            },
+            nfc_expansions: ExpansionsPatch {
+                expansions: tables.next(),
+            },
+            nfkc_expansions: ExpansionsPatch {
+                expansions: tables.next(),
+            },
+        };
+
+        data.validate()?;
+
+        Ok(data)
+    }
+
+    /// записать данные в бинарный формат
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let lengths = [
+            self.nfd.index.len(),
+            self.nfd.data.len(),
+            self.nfd.expansions.len(),
+            self.nfd.quick_check.len(),
+            self.nfd.quick_check_index.len(),
+            self.nfd.quick_check_rows.len(),
+            self.nfkd.index.len(),
+            self.nfkd.data.len(),
+            self.nfkd.expansions.len(),
+            self.nfkd.quick_check.len(),
+            self.nfkd.quick_check_index.len(),
+            self.nfkd.quick_check_rows.len(),
+            self.compositions.compositions.len(),
+            self.compositions.displacements.len(),
+            self.compositions.slots.len(),
+            self.nfc_expansions.expansions.len(),
+            self.nfkc_expansions.expansions.len(),
+        ];
+
+        let mut sizes = [0; TABLES];
+
+        for (i, size) in sizes.iter_mut().enumerate() {
+            *size = lengths[i] * TABLE_ELEMENT_SIZES[i];
+        }
+
+        let mut bytes = Vec::with_capacity(blob_size(&sizes));
+
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend_from_slice(&[
+            self.unicode_version.major,
+            self.unicode_version.minor,
+            self.unicode_version.update,
+            0,
+        ]);
+        bytes.extend_from_slice(&self.nfd.continuous_block_end.to_le_bytes());
+        bytes.extend_from_slice(&self.nfkd.continuous_block_end.to_le_bytes());
+
+        for length in lengths {
+            bytes.extend_from_slice(&(length as u32).to_le_bytes());
+        }
+
+        // контрольная сумма записывается после таблиц
+        bytes.extend_from_slice(&[0; 4]);
+
+        write_table(&mut bytes, self.nfd.index, u16::to_le_bytes);
+        write_table(&mut bytes, self.nfd.data, u32::to_le_bytes);
+        write_table(&mut bytes, self.nfd.expansions, u32::to_le_bytes);
+        write_table(&mut bytes, self.nfd.quick_check, u64::to_le_bytes);
+        write_table(&mut bytes, self.nfd.quick_check_index, u16::to_le_bytes);
+        write_table(&mut bytes, self.nfd.quick_check_rows, u64::to_le_bytes);
+        write_table(&mut bytes, self.nfkd.index, u16::to_le_bytes);
+        write_table(&mut bytes, self.nfkd.data, u32::to_le_bytes);
+        write_table(&mut bytes, self.nfkd.expansions, u32::to_le_bytes);
+        write_table(&mut bytes, self.nfkd.quick_check, u64::to_le_bytes);
+        write_table(&mut bytes, self.nfkd.quick_check_index, u16::to_le_bytes);
+        write_table(&mut bytes, self.nfkd.quick_check_rows, u64::to_le_bytes);
+        write_table(&mut bytes, self.compositions.compositions, u64::to_le_bytes);
+        write_table(
+            &mut bytes,
+            self.compositions.displacements,
+            u16::to_le_bytes,
+        );
+        write_table(&mut bytes, self.compositions.slots, u16::to_le_bytes);
+        write_table(&mut bytes, self.nfc_expansions.expansions, u32::to_le_bytes);
+        write_table(
+            &mut bytes,
+            self.nfkc_expansions.expansions,
+            u32::to_le_bytes,
+        );
+
+        let checksum = fnv1a(&bytes[HEADER_SIZE ..]);
+        bytes[92 .. 96].copy_from_slice(&checksum.to_le_bytes());
+
+        bytes
+    }
+}
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// нормализатор NFC (is_canonical) или NFKC из данных в бинарном формате, без копирования
+    pub fn from_bytes(bytes: &'a [u8], is_canonical: bool) -> Result<Self, DataError>
+    {
+        NormalizationData::from_bytes(bytes)?.composing(is_canonical)
+    }
+}
+
+impl<'a> DecomposingNormalizer<'a>
+{
+    /// нормализатор NFD (is_canonical) или NFKD из данных в бинарном формате, без копирования
+    pub fn from_bytes(bytes: &'a [u8], is_canonical: bool) -> Result<Self, DataError>
+    {
+        NormalizationData::from_bytes(bytes)?.decomposing(is_canonical)
+    }
+}
+
+/// последовательное чтение таблиц
+struct Tables<'b>
+{
+    bytes: &'b [u8],
+    offset: usize,
+    sizes: [usize; TABLES],
+    table: usize,
+}
+
+impl<'b> Tables<'b>
+{
+    /// следующая таблица. размер данных и выравнивание уже проверены
+    fn next<T: Element>(&mut self) -> &'b [T]
+    {
+        let start = align(self.offset);
+        let end = start + self.sizes[self.table];
+
+        self.offset = end;
+        self.table += 1;
+
+        // SAFETY: T - целочисленный тип, для которого допустимы любые значения байтов
+        let (prefix, table, suffix) = unsafe { self.bytes[start .. end].align_to::<T>() };
+
+        debug_assert!(prefix.is_empty() && suffix.is_empty());
+
+        table
+    }
+}
+
+/// целочисленные типы элементов таблиц
+trait Element: Copy {}
+
+impl Element for u16 {}
+impl Element for u32 {}
+impl Element for u64 {}
+
+/// дописать таблицу, выровняв её начало
+fn write_table<T: Copy, const N: usize>(bytes: &mut Vec<u8>, table: &[T], to_le: fn(T) -> [u8; N])
+{
+    bytes.resize(align(bytes.len()), 0);
+    table
+        .iter()
+        .for_each(|&value| bytes.extend_from_slice(&to_le(value)));
+}
+
+/// полный размер данных с таблицами заданных размеров (в байтах)
+fn blob_size(sizes: &[usize; TABLES]) -> usize
+{
+    sizes.iter().fold(HEADER_SIZE, |offset, &size| {
+        align(offset).saturating_add(size)
+    })
+}
+
+/// смещение, выровненное для начала таблицы
+#[inline(always)]
+fn align(offset: usize) -> usize
+{
+    offset.saturating_add(TABLE_ALIGN - 1) & !(TABLE_ALIGN - 1)
+}
+
+#[inline(always)]
+fn read_u32(bytes: &[u8], offset: usize) -> u32
+{
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+/// контрольная сумма FNV-1a
+fn fnv1a(bytes: &[u8]) -> u32
+{
+    bytes.iter().fold(0x811C9DC5, |hash, &byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x01000193)
+    })
+}
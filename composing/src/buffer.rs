@@ -0,0 +1,103 @@
+use alloc::vec::Vec;
+use core::ops::{Deref, DerefMut};
+
+use crate::codepoint::Codepoint;
+
+/// размер буфера, размещаемого без обращения к куче. формат Stream-Safe Text (UAX #15) ограничивает
+/// последовательность стартером и 30 нестартерами
+pub const INLINE_BUFFER_CAPACITY: usize = 32;
+
+/// буфер комбинируемых кодпоинтов: стартер (если есть) и следующие за ним нестартеры
+pub trait Buffer: DerefMut<Target = [Codepoint]>
+{
+    /// добавить кодпоинт
+    fn push(&mut self, codepoint: Codepoint);
+
+    /// очистить буфер
+    fn clear(&mut self);
+}
+
+impl Buffer for Vec<Codepoint>
+{
+    #[inline(always)]
+    fn push(&mut self, codepoint: Codepoint)
+    {
+        Vec::push(self, codepoint)
+    }
+
+    #[inline(always)]
+    fn clear(&mut self)
+    {
+        Vec::clear(self)
+    }
+}
+
+/// буфер фиксированного размера. кодпоинты, не поместившиеся в буфер, отбрасываются,
+/// а буфер помечается переполненным - результат нормализации в таком случае неверен
+pub struct InlineBuffer
+{
+    codepoints: [Codepoint; INLINE_BUFFER_CAPACITY],
+    length: usize,
+    overflow: bool,
+}
+
+impl InlineBuffer
+{
+    #[inline(always)]
+    pub fn new() -> Self
+    {
+        Self {
+            codepoints: [Codepoint::from_baked(0); INLINE_BUFFER_CAPACITY],
+            length: 0,
+            overflow: false,
+        }
+    }
+
+    /// была ли попытка добавить кодпоинт в заполненный буфер?
+    #[inline(always)]
+    pub fn is_overflowed(&self) -> bool
+    {
+        self.overflow
+    }
+}
+
+impl Deref for InlineBuffer
+{
+    type Target = [Codepoint];
+
+    #[inline(always)]
+    fn deref(&self) -> &[Codepoint]
+    {
+        &self.codepoints[.. self.length]
+    }
+}
+
+impl DerefMut for InlineBuffer
+{
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut [Codepoint]
+    {
+        &mut self.codepoints[.. self.length]
+    }
+}
+
+impl Buffer for InlineBuffer
+{
+    #[inline(always)]
+    fn push(&mut self, codepoint: Codepoint)
+    {
+        match self.length < INLINE_BUFFER_CAPACITY {
+            true => {
+                self.codepoints[self.length] = codepoint;
+                self.length += 1;
+            }
+            false => self.overflow = true,
+        }
+    }
+
+    #[inline(always)]
+    fn clear(&mut self)
+    {
+        self.length = 0;
+    }
+}
@@ -13,7 +13,7 @@ impl From<Codepoint> for char
 
 impl core::fmt::Debug for Codepoint
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
     {
         write!(
             f,
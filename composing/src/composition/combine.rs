@@ -1,3 +1,5 @@
+use crate::data::CompositionData;
+
 /// информация о комбинировании стартера с идущими следом кодпоинтами
 #[derive(Debug, Clone, Copy)]
 pub struct Combining(u16);
@@ -52,27 +54,75 @@ pub enum CombineResult
 
 /// скомбинировать два кодпоинта
 #[inline(always)]
-pub fn combine(combining: Combining, second: u32, compositions_table: &[u64]) -> CombineResult
+pub fn combine(combining: Combining, second: u32, compositions: &CompositionData) -> CombineResult
+{
+    let index = match composition_index(combining, second, compositions) {
+        Some(index) => index,
+        None => return CombineResult::None,
+    };
+
+    let entry = compositions.compositions[index];
+
+    let code = (entry >> 18) as u32 & 0x3FFFF;
+    let combining = (entry >> 48) as u16;
+
+    match combining {
+        0 => CombineResult::Final(code),
+        _ => CombineResult::Combined(code, Combining::from(combining)),
+    }
+}
+
+/// индекс записи композиции: запись ищется по идеальному хешу пары (начало группы вариантов
+/// комбинирования, второй кодпоинт). ячейка хеша может содержать чужую запись или быть пустой,
+/// поэтому запись проверяется: она должна принадлежать группе и содержать второй кодпоинт.
+/// не встраивается: иначе код поиска ухудшает распределение регистров в быстрых циклах.
+/// в коротких группах (греческие гласные, 4 - 8 записей, тонос - вторая запись) просмотр группы
+/// находит запись быстрее хеша, но просмотр коротких групп перед поиском по хешу замедляет
+/// латиницу с длинными группами сильнее, чем ускоряет греческий
+#[inline(never)]
+pub fn composition_index(
+    combining: Combining,
+    second: u32,
+    compositions: &CompositionData,
+) -> Option<usize>
 {
     let first = combining.index();
-    let last = first + combining.count();
 
-    for entry in &compositions_table[first as usize .. last as usize] {
-        let entry = *entry;
+    let bucket = reduce(
+        composition_hash(first, second, 0),
+        compositions.displacements.len(),
+    );
+    let displacement = *compositions.displacements.get(bucket)?;
 
-        let entry_codepoint = entry as u32 & 0x3FFFF;
+    let slot = reduce(
+        composition_hash(first, second, displacement as u32),
+        compositions.slots.len(),
+    );
+    let index = *compositions.slots.get(slot)?;
 
-        // кодпоинты комбинируются
-        if entry_codepoint == second {
-            let code = (entry >> 18) as u32 & 0x3FFFF;
-            let combining = (entry >> 48) as u16;
+    // индекс вне группы (в том числе у некомбинируемого стартера - группа пуста)
+    if index.wrapping_sub(first) >= combining.count() {
+        return None;
+    }
 
-            return match combining {
-                0 => CombineResult::Final(code),
-                _ => CombineResult::Combined(code, Combining::from(combining)),
-            };
-        }
+    match compositions.compositions[index as usize] as u32 & 0x3FFFF == second {
+        true => Some(index as usize),
+        false => None,
     }
+}
+
+/// хеш пары (начало группы в таблице композиций, второй кодпоинт) со смещением корзины
+#[inline(always)]
+fn composition_hash(group: u16, second: u32, displacement: u32) -> u32
+{
+    let key = second | (group as u32) << 21;
 
-    CombineResult::None
+    (key.wrapping_mul(0x9E3779B1) ^ displacement).wrapping_mul(0x85EBCA6B)
+}
+
+/// индекс в таблице заданной длины по старшим битам хеша
+#[inline(always)]
+fn reduce(hash: u32, length: usize) -> usize
+{
+    ((hash as u64 * length as u64) >> 32) as usize
 }
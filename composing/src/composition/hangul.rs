@@ -1,3 +1,4 @@
+use crate::sink::Output;
 use crate::write_char;
 
 use super::Combining;
@@ -42,7 +43,7 @@ pub enum HangulVT
 /// скомбинировать чамо хангыль (V / T) с предыдущим кодпоинтом
 #[inline(never)]
 pub fn combine_and_write_hangul_vt(
-    result: &mut String,
+    result: &mut impl Output,
     jamo: u32,
     combining: &mut Combining,
 ) -> bool
@@ -95,6 +96,33 @@ pub fn combine_and_write_hangul_vt(
     true
 }
 
+/// слог LV из ведущей согласной и гласной чамо, если они комбинируются
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
+#[inline(always)]
+pub fn compose_hangul_lv(l: u32, v: u32) -> Option<u32>
+{
+    let l = l.wrapping_sub(HANGUL_L_BASE);
+    let v = v.wrapping_sub(HANGUL_V_BASE);
+
+    match l < HANGUL_L_COUNT && v < HANGUL_V_COUNT {
+        true => Some(HANGUL_S_BASE + l * HANGUL_N_COUNT + v * HANGUL_T_BLOCK_SIZE),
+        false => None,
+    }
+}
+
+/// слог LVT из слога LV и завершающей согласной чамо, если она комбинируется
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
+#[inline(always)]
+pub fn compose_hangul_lvt(lv: u32, t: u32) -> Option<u32>
+{
+    let t = t.wrapping_sub(HANGUL_T_BASE);
+
+    match t < HANGUL_T_COUNT {
+        true => Some(lv + t + 1),
+        false => None,
+    }
+}
+
 /// если кодпоинт является гласной или завершающей согласной чамо хангыль - получаем значения
 #[inline(always)]
 fn get_vt(code: u32) -> Option<HangulVT>
@@ -113,3 +141,22 @@ fn get_vt(code: u32) -> Option<HangulVT>
         }
     }
 }
+
+/// записать декомпозицию слога хангыль (LV / LVT)
+#[cfg(any(feature = "nfd", feature = "nfkd"))]
+#[inline(always)]
+pub fn write_hangul_decomposition(result: &mut impl Output, code: u32)
+{
+    let s = code - HANGUL_S_BASE;
+
+    let l = s / HANGUL_N_COUNT;
+    let v = (s % HANGUL_N_COUNT) / HANGUL_T_BLOCK_SIZE;
+    let t = s % HANGUL_T_BLOCK_SIZE;
+
+    write_char(result, HANGUL_L_BASE + l);
+    write_char(result, HANGUL_V_BASE + v);
+
+    if t != 0 {
+        write_char(result, HANGUL_T_BASE + t - 1);
+    }
+}
@@ -1,20 +1,27 @@
+use crate::buffer::Buffer;
 use crate::codepoint::Codepoint;
+use crate::data::CompositionData;
+use crate::sink::Output;
 use crate::write_char;
 
 pub use combine::*;
 mod combine;
 
 pub use hangul::combine_and_write_hangul_vt;
+#[cfg(any(feature = "nfd", feature = "nfkd"))]
+pub use hangul::write_hangul_decomposition;
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
+pub use hangul::{compose_hangul_lv, compose_hangul_lvt};
 mod hangul;
 
 /// композиция кодпоинтов и их запись
 /// предполагается, что буффер не содержит несколько стартеров, стартер может стоять только в начале последовательности
 #[inline(always)]
 pub fn combine_and_write(
-    result: &mut String,
-    buffer: &mut Vec<Codepoint>,
+    result: &mut impl Output,
+    buffer: &mut impl Buffer,
     mut combining: Combining,
-    compositions_table: &[u64],
+    compositions: &CompositionData,
 )
 {
     match buffer.len() {
@@ -44,24 +51,25 @@ pub fn combine_and_write(
     let mut starter = buffer[0].code();
     let nonstarters = &mut buffer[1 ..];
 
-    let mut tail = String::new();
-    let mut recent_skipped_ccc = 0;
-
     if nonstarters.len() > 1 {
         nonstarters.sort_by_key(|c| c.ccc());
     }
 
-    let mut iter = nonstarters.iter();
+    // нескомбинированные нестартеры сдвигаются к началу, на место скомбинированных
+    let mut kept = 0;
+    let mut recent_skipped_ccc = 0;
 
-    for nonstarter in iter.by_ref() {
+    for i in 0 .. nonstarters.len() {
+        let nonstarter = nonstarters[i];
         let ccc = nonstarter.ccc();
 
         if ccc == recent_skipped_ccc {
-            tail.push(char::from(*nonstarter));
+            nonstarters[kept] = nonstarter;
+            kept += 1;
             continue;
         }
 
-        let combined = combine(combining, nonstarter.code(), compositions_table);
+        let combined = combine(combining, nonstarter.code(), compositions);
 
         match combined {
             CombineResult::Combined(new_starter, new_combining) => {
@@ -70,32 +78,36 @@ pub fn combine_and_write(
             }
             CombineResult::Final(new_starter) => {
                 starter = new_starter;
-                iter.for_each(|c| tail.push(char::from(*c)));
+                nonstarters.copy_within(i + 1 .., kept);
+                kept += nonstarters.len() - i - 1;
 
                 break;
             }
             CombineResult::None => {
-                tail.push(char::from(*nonstarter));
+                nonstarters[kept] = nonstarter;
+                kept += 1;
                 recent_skipped_ccc = ccc;
             }
         }
     }
 
-    buffer.clear();
-
     write_char(result, starter);
-    result.push_str(&tail);
+    nonstarters[.. kept]
+        .iter()
+        .for_each(|c| result.push(char::from(*c)));
+
+    buffer.clear();
 }
 
 /// скомбинировать с предыдущим
 #[inline(always)]
 pub fn combine_backwards(
-    buffer: &mut Vec<Codepoint>,
-    result: &mut String,
+    buffer: &mut impl Buffer,
+    result: &mut impl Output,
     code: u32,
     mut combining: Combining,
     backwards_combining: Combining,
-    compositions: &[u64],
+    compositions: &CompositionData,
 ) -> Combining
 {
     combine_and_write(result, buffer, combining, compositions);
@@ -1,4 +1,11 @@
+use crate::binary::DataError;
+use crate::version::UnicodeVersion;
+use crate::LAST_DECOMPOSING_CODEPOINT_BLOCK;
+use crate::{ComposingForm, ComposingNormalizer};
+use crate::{DecomposingForm, DecomposingNormalizer};
+
 /// структура хранимых данных для нормализации
+#[derive(Clone, Copy)]
 pub struct DecompositionData<'a>
 {
     /// индекс блока
@@ -7,48 +14,315 @@ pub struct DecompositionData<'a>
     pub data: &'a [u32],
     /// данные кодпоинтов, которые не вписываются в основную часть
     pub expansions: &'a [u32],
+    /// последовательности UTF-8, проходящие быструю проверку, по первым двум байтам: 64 элемента
+    /// для NF(K)C, затем 64 для NF(K)D. элемент - младшие 6 бит первого байта, бит - младшие 6 бит
+    /// второго: 2-байтовые последовательности - отдельные кодпоинты, 3-байтовые - блоки
+    /// по 64 кодпоинта, 4-байтовые не проходят проверку
+    pub quick_check: &'a [u64],
+    /// номера строк quick_check_rows для отдельных 3-байтовых последовательностей: элемент - младшие
+    /// 4 бита первого байта и младшие 6 бит второго, 1024 элемента для NF(K)C, затем 1024 для NF(K)D
+    pub quick_check_index: &'a [u16],
+    /// строки быстрой проверки 3-байтовых последовательностей, бит - младшие 6 бит третьего байта
+    pub quick_check_rows: &'a [u64],
     /// с U+0000 и до этого кодпоинта включительно блоки в data идут последовательно
     pub continuous_block_end: u32,
+    /// версия Unicode, из которой запечены данные
+    pub unicode_version: UnicodeVersion,
 }
 
 /// данные для комбинирования кодпоинтов
+#[derive(Clone, Copy)]
 pub struct CompositionData<'a>
 {
+    /// записи композиций, сгруппированные по первому (или второму) кодпоинту
     pub compositions: &'a [u64],
+    /// смещения хеша для корзин идеального хеширования пар (начало группы, второй кодпоинт)
+    pub displacements: &'a [u16],
+    /// индексы записей композиций по хешу пары
+    pub slots: &'a [u16],
 }
 
 /// замена декомпозиций для NF(K)C
+#[derive(Clone, Copy)]
 pub struct ExpansionsPatch<'a>
 {
     pub expansions: &'a [u32],
 }
 
-/// данные для NFD-нормализации
-pub fn nfd<'a>() -> DecompositionData<'a>
+/// данные всех форм нормализации: таблицы, общие для нескольких форм, хранятся один раз
+#[derive(Clone, Copy)]
+pub struct NormalizationData<'a>
 {
-    include!("./../../data/nfd.txt")
+    /// версия Unicode
+    pub unicode_version: UnicodeVersion,
+    /// декомпозиция NFD, используется также NFC
+    pub nfd: DecompositionData<'a>,
+    /// декомпозиция NFKD, используется также NFKC
+    pub nfkd: DecompositionData<'a>,
+    /// композиции, общие для NFC и NFKC
+    pub compositions: CompositionData<'a>,
+    /// замена декомпозиций для NFC
+    pub nfc_expansions: ExpansionsPatch<'a>,
+    /// замена декомпозиций для NFKC
+    pub nfkc_expansions: ExpansionsPatch<'a>,
 }
 
-/// данные для NFKD-нормализации
-pub fn nfkd<'a>() -> DecompositionData<'a>
+impl NormalizationData<'static>
 {
-    include!("./../../data/nfkd.txt")
+    /// запечённые данные последней версии Unicode
+    pub const fn baked() -> Self
+    {
+        v15_1::baked()
+    }
+
+    /// запечённые данные заданной версии Unicode. данные предыдущих версий подключаются
+    /// возможностями крейта (unicode_14_0)
+    pub const fn baked_with(version: UnicodeVersion) -> Result<Self, DataError>
+    {
+        match version {
+            UnicodeVersion::V15_1 => Ok(v15_1::baked()),
+            #[cfg(feature = "unicode_14_0")]
+            UnicodeVersion::V14_0 => Ok(v14_0::baked()),
+            _ => Err(DataError::UnsupportedUnicodeVersion(version)),
+        }
+    }
 }
 
-/// данные комбинирования для NF(K)C-нормализации
-pub fn compositions<'a>() -> CompositionData<'a>
+impl<'a> NormalizationData<'a>
 {
-    include!("./../../data/compositions.txt")
+    /// в наборе есть таблицы NFD. таблицы форм, отключённых возможностями крейта, экспортируются
+    /// пустыми
+    pub const fn has_nfd(&self) -> bool
+    {
+        has_tables(&self.nfd)
+    }
+
+    /// в наборе есть таблицы NFKD
+    pub const fn has_nfkd(&self) -> bool
+    {
+        has_tables(&self.nfkd)
+    }
+
+    /// в наборе есть таблицы NFC: декомпозиция NFD, композиции и замена расширений NFC
+    pub const fn has_nfc(&self) -> bool
+    {
+        self.has_nfd()
+            && has_compositions(&self.compositions)
+            && !self.nfc_expansions.expansions.is_empty()
+    }
+
+    /// в наборе есть таблицы NFKC: декомпозиция NFKD, композиции и замена расширений NFKC
+    pub const fn has_nfkc(&self) -> bool
+    {
+        self.has_nfkd()
+            && has_compositions(&self.compositions)
+            && !self.nfkc_expansions.expansions.is_empty()
+    }
+
+    /// NFC- (is_canonical) или NFKC-нормализатор, использующий данные набора. если форма отключена
+    /// возможностями крейта - DataError::FormDisabled, если таблиц формы в наборе нет -
+    /// DataError::MissingForm
+    pub fn composing(&self, is_canonical: bool) -> Result<ComposingNormalizer<'a>, DataError>
+    {
+        match ComposingForm::from_canonical(is_canonical)? {
+            #[cfg(feature = "nfc")]
+            ComposingForm::Nfc => match self.has_nfc() {
+                true => Ok(self.nfc()),
+                false => Err(DataError::MissingForm("NFC")),
+            },
+            #[cfg(feature = "nfkc")]
+            ComposingForm::Nfkc => match self.has_nfkc() {
+                true => Ok(self.nfkc()),
+                false => Err(DataError::MissingForm("NFKC")),
+            },
+        }
+    }
+
+    /// NFD- (is_canonical) или NFKD-нормализатор, использующий данные набора. если форма отключена
+    /// возможностями крейта - DataError::FormDisabled, если таблиц формы в наборе нет -
+    /// DataError::MissingForm
+    pub fn decomposing(&self, is_canonical: bool) -> Result<DecomposingNormalizer<'a>, DataError>
+    {
+        match DecomposingForm::from_canonical(is_canonical)? {
+            #[cfg(feature = "nfd")]
+            DecomposingForm::Nfd => match self.has_nfd() {
+                true => Ok(self.nfd()),
+                false => Err(DataError::MissingForm("NFD")),
+            },
+            #[cfg(feature = "nfkd")]
+            DecomposingForm::Nfkd => match self.has_nfkd() {
+                true => Ok(self.nfkd()),
+                false => Err(DataError::MissingForm("NFKD")),
+            },
+        }
+    }
+
+    /// NFC-нормализатор, использующий данные набора. таблицы формы должны быть в наборе
+    /// (см. has_nfc), иначе - паника, как в from_baked
+    #[cfg(feature = "nfc")]
+    pub const fn nfc(&self) -> ComposingNormalizer<'a>
+    {
+        ComposingNormalizer::from_baked(self.nfd, self.compositions, self.nfc_expansions, true)
+    }
+
+    /// NFKC-нормализатор, использующий данные набора. таблицы формы должны быть в наборе
+    /// (см. has_nfkc), иначе - паника, как в from_baked
+    #[cfg(feature = "nfkc")]
+    pub const fn nfkc(&self) -> ComposingNormalizer<'a>
+    {
+        ComposingNormalizer::from_baked(self.nfkd, self.compositions, self.nfkc_expansions, false)
+    }
+
+    /// NFD-нормализатор, использующий данные набора. таблицы формы должны быть в наборе
+    /// (см. has_nfd), иначе - паника, как в from_baked
+    #[cfg(feature = "nfd")]
+    pub const fn nfd(&self) -> DecomposingNormalizer<'a>
+    {
+        DecomposingNormalizer::from_baked(self.nfd, true)
+    }
+
+    /// NFKD-нормализатор, использующий данные набора. таблицы формы должны быть в наборе
+    /// (см. has_nfkd), иначе - паника, как в from_baked
+    #[cfg(feature = "nfkd")]
+    pub const fn nfkd(&self) -> DecomposingNormalizer<'a>
+    {
+        DecomposingNormalizer::from_baked(self.nfkd, false)
+    }
 }
 
-/// замена части декомпозиций для NFC
-pub fn nfc_expansions<'a>() -> ExpansionsPatch<'a>
+/// есть хотя бы одна таблица декомпозиции
+const fn has_tables(data: &DecompositionData) -> bool
 {
-    include!("./../../data/nfc.txt")
+    !(data.index.is_empty()
+        && data.data.is_empty()
+        && data.expansions.is_empty()
+        && data.quick_check.is_empty()
+        && data.quick_check_index.is_empty()
+        && data.quick_check_rows.is_empty())
 }
 
-/// замена части декомпозиций для NFKC
-pub fn nfkc_expansions<'a>() -> ExpansionsPatch<'a>
+/// есть хотя бы одна таблица композиций
+const fn has_compositions(data: &CompositionData) -> bool
 {
-    include!("./../../data/nfkc.txt")
+    !(data.compositions.is_empty() && data.displacements.is_empty() && data.slots.is_empty())
 }
+
+/// массив, выровненный по 16 байт
+#[repr(C, align(16))]
+struct AlignedArray<T, const N: usize>([T; N]);
+
+impl<T: Copy, const N: usize> AlignedArray<T, N>
+{
+    /// скопировать запечённые данные в выровненный массив на этапе компиляции
+    const fn new(source: &[T]) -> Self
+    {
+        assert!(source.len() == N && N != 0);
+
+        let mut array = [source[0]; N];
+        let mut i = 1;
+
+        while i < N {
+            array[i] = source[i];
+            i += 1;
+        }
+
+        Self(array)
+    }
+}
+
+/// выровненная статическая копия запечённого слайса. если таблица не нужна ни одной из включённых
+/// форм нормализации - пустой слайс, и данные не попадают в сборку
+macro_rules! baked_table {
+    ($type:ty = $source:expr, $($feature:literal),+) => {{
+        #[cfg(any($(feature = $feature),+))]
+        let table: &[$type] = {
+            static TABLE: AlignedArray<$type, { $source.len() }> = AlignedArray::new($source);
+            &TABLE.0
+        };
+        #[cfg(not(any($(feature = $feature),+)))]
+        let table: &[$type] = &[];
+
+        table
+    }};
+}
+
+/// запечённые данные версии Unicode из подкаталога data
+macro_rules! baked_data {
+    ($module:ident, $dir:literal) => {
+        mod $module
+        {
+            use super::*;
+
+            const NFD: DecompositionData = include!(concat!("./../../data/", $dir, "/nfd.txt"));
+            const NFKD: DecompositionData = include!(concat!("./../../data/", $dir, "/nfkd.txt"));
+            #[cfg(any(feature = "nfc", feature = "nfkc"))]
+            const COMPOSITIONS: CompositionData =
+                include!(concat!("./../../data/", $dir, "/compositions.txt"));
+            #[cfg(feature = "nfc")]
+            const NFC_EXPANSIONS: ExpansionsPatch =
+                include!(concat!("./../../data/", $dir, "/nfc.txt"));
+            #[cfg(feature = "nfkc")]
+            const NFKC_EXPANSIONS: ExpansionsPatch =
+                include!(concat!("./../../data/", $dir, "/nfkc.txt"));
+            const LAST_DECOMPOSING_CODEPOINT: u32 = include!(concat!(
+                "./../../data/",
+                $dir,
+                "/last_decomposing_codepoint.txt"
+            ));
+
+            // блоки после LAST_DECOMPOSING_CODEPOINT_BLOCK не читаются из индекса
+            const _: () = assert!(
+                LAST_DECOMPOSING_CODEPOINT >> (18 - 11) <= LAST_DECOMPOSING_CODEPOINT_BLOCK as u32
+            );
+
+            /// данные всех форм нормализации. таблицы форм, отключённых возможностями крейта, пусты
+            pub const fn baked() -> NormalizationData<'static>
+            {
+                NormalizationData {
+                    unicode_version: NFD.unicode_version,
+                    nfd: DecompositionData {
+                        index: baked_table!(u16 = NFD.index, "nfc", "nfd"),
+                        data: baked_table!(u32 = NFD.data, "nfc", "nfd"),
+                        expansions: baked_table!(u32 = NFD.expansions, "nfc", "nfd"),
+                        quick_check: baked_table!(u64 = NFD.quick_check, "nfc", "nfd"),
+                        quick_check_index: baked_table!(u16 = NFD.quick_check_index, "nfc", "nfd"),
+                        quick_check_rows: baked_table!(u64 = NFD.quick_check_rows, "nfc", "nfd"),
+                        ..NFD
+                    },
+                    nfkd: DecompositionData {
+                        index: baked_table!(u16 = NFKD.index, "nfkc", "nfkd"),
+                        data: baked_table!(u32 = NFKD.data, "nfkc", "nfkd"),
+                        expansions: baked_table!(u32 = NFKD.expansions, "nfkc", "nfkd"),
+                        quick_check: baked_table!(u64 = NFKD.quick_check, "nfkc", "nfkd"),
+                        quick_check_index: baked_table!(
+                            u16 = NFKD.quick_check_index,
+                            "nfkc",
+                            "nfkd"
+                        ),
+                        quick_check_rows: baked_table!(u64 = NFKD.quick_check_rows, "nfkc", "nfkd"),
+                        ..NFKD
+                    },
+                    compositions: CompositionData {
+                        compositions: baked_table!(u64 = COMPOSITIONS.compositions, "nfc", "nfkc"),
+                        displacements: baked_table!(
+                            u16 = COMPOSITIONS.displacements,
+                            "nfc",
+                            "nfkc"
+                        ),
+                        slots: baked_table!(u16 = COMPOSITIONS.slots, "nfc", "nfkc"),
+                    },
+                    nfc_expansions: ExpansionsPatch {
+                        expansions: baked_table!(u32 = NFC_EXPANSIONS.expansions, "nfc"),
+                    },
+                    nfkc_expansions: ExpansionsPatch {
+                        expansions: baked_table!(u32 = NFKC_EXPANSIONS.expansions, "nfkc"),
+                    },
+                }
+            }
+        }
+    };
+}
+
+baked_data!(v15_1, "15.1.0");
+#[cfg(feature = "unicode_14_0")]
+baked_data!(v14_0, "14.0.0");
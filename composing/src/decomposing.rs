@@ -0,0 +1,448 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::codepoint::Codepoint;
+use crate::data;
+use crate::sink::{NormalizeSink, Output, SinkOutput};
+use crate::version::UnicodeVersion;
+use crate::{quick_check_index, quick_check_table, DecomposingForm};
+// используются только методами нормализации NFD и NFKD
+#[cfg(any(feature = "nfd", feature = "nfkd"))]
+use crate::{
+    binary::DataError, composition::write_hangul_decomposition, data::NormalizationData,
+    get_decomposition_value, passes_quick_check, passes_quick_check_row, slice::iter::CharsIter,
+    write_char, write_str, MARKER_EXPANSION, MARKER_EXPANSION_COMBINED_EMPTY,
+    MARKER_EXPANSION_COMBINED_PATCH, MARKER_HANGUL_SYLLABLE, MARKER_NONSTARTER, MARKER_SINGLETON,
+};
+
+/// нормализатор NF(K)D. данные не копируются, поэтому клонирование дёшево
+#[derive(Clone)]
+#[repr(C, align(16))]
+pub struct DecomposingNormalizer<'a>
+{
+    /// основные данные
+    data: &'a [u32],
+    /// индекс блока
+    index: &'a [u16],
+    /// данные кодпоинтов, которые не вписываются в основную часть
+    expansions: &'a [u32],
+    /// с U+0000 и до этого кодпоинта включительно блоки в data идут последовательно
+    continuous_block_end: u32,
+    /// 2- и 3-байтовые последовательности - стартеры без декомпозиции, по первым двум байтам.
+    /// запечённая таблица формы - не копируется в каждый нормализатор
+    quick_check: &'a [u64; 64],
+    /// номера строк быстрой проверки отдельных 3-байтовых последовательностей формы
+    quick_check_index: &'a [u16; 1024],
+    /// строки быстрой проверки 3-байтовых последовательностей
+    quick_check_rows: &'a [u64],
+    /// NFD или NFKD
+    form: DecomposingForm,
+    /// версия Unicode данных
+    unicode_version: UnicodeVersion,
+}
+
+// методы нормализации вынесены в макрос в целях оптимизации
+#[cfg(any(feature = "nfd", feature = "nfkd"))]
+macro_rules! normalizer_methods {
+    ($normalize_method:ident, $forward:ident, $fast_forward:ident, $first_code_boundary:expr) => {
+        #[inline(always)]
+        fn $normalize_method(
+            &self,
+            input: &str,
+            result: &mut impl Output,
+            buffer: &mut Vec<Codepoint>,
+        )
+        {
+            let iter = &mut CharsIter::new(input);
+
+            loop {
+                // приёмник вернул ошибку - остаток строки не нормализуем
+                if result.is_stopped() {
+                    return;
+                }
+
+                let entry = match !buffer.is_empty() {
+                    true => match self.$forward(iter, result, buffer) {
+                        Some(entry) => Some(entry),
+                        None => continue,
+                    },
+                    false => self.$fast_forward(iter, result),
+                };
+
+                match entry {
+                    Some((dec_value, code)) => {
+                        self.handle_dec_value(dec_value, code, result, buffer);
+                        iter.set_breakpoint();
+                    }
+                    None => return,
+                }
+            }
+        }
+
+        /// если буфер не пуст, мы не можем перейти к быстрой проверке.
+        /// прочитаем следующий кодпоинт, и если он стартер без декомпозиции - отсортируем и запишем буфер
+        #[inline(always)]
+        fn $forward(
+            &self,
+            iter: &mut CharsIter,
+            result: &mut impl Output,
+            buffer: &mut Vec<Codepoint>,
+        ) -> Option<(u32, u32)>
+        {
+            iter.set_breakpoint();
+
+            if !iter.is_empty() {
+                let first = unsafe { iter.next_unchecked() };
+
+                if first >= $first_code_boundary {
+                    let code = unsafe { iter.next_nonascii_bytes_unchecked(first) };
+                    let dec_value = self.get_decomposition_value(code);
+
+                    if !is_starter_without_decomposition(dec_value) {
+                        return Some((dec_value, code));
+                    }
+                }
+            }
+
+            sort_and_write(result, buffer);
+            None
+        }
+
+        /// цикл быстрой проверки, является-ли часть строки уже нормализованной
+        #[inline(always)]
+        fn $fast_forward(
+            &self,
+            iter: &mut CharsIter,
+            result: &mut impl Output,
+        ) -> Option<(u32, u32)>
+        {
+            Some(loop {
+                if iter.is_empty() {
+                    write_str(result, iter.ending_slice());
+                    return None;
+                }
+
+                let first = unsafe { iter.next_unchecked() };
+
+                // символы до U+00C0 (NFD) и U+00A0 (NFKD) не имеют декомпозиции и являются стартерами,
+                // поэтому в качестве границы используем первый байт их UTF-8 последовательностей
+                // 2-байтовые последовательности проверяем по битовой карте, без декодирования. за такими
+                // байтами обычно идут такие же - пропускаем их блоками, вместе с 3-байтовыми из блоков,
+                // целиком прошедших проверку
+                if first < $first_code_boundary
+                    || first < 0xE0
+                        && passes_quick_check(self.quick_check, first, unsafe {
+                            iter.peek_unchecked()
+                        })
+                {
+                    iter.skip_passing($first_code_boundary, self.quick_check);
+                    continue;
+                }
+
+                // отдельные 3-байтовые последовательности - по строкам, также без декодирования
+                if first & 0xF0 == 0xE0
+                    && passes_quick_check_row(
+                        self.quick_check_index,
+                        self.quick_check_rows,
+                        first,
+                        unsafe { iter.peek_two_unchecked() },
+                    )
+                {
+                    unsafe { iter.skip_unchecked(2) };
+                    continue;
+                }
+
+                let code = unsafe { iter.next_nonascii_bytes_unchecked(first) };
+                let dec_value = self.get_decomposition_value(code);
+
+                if is_starter_without_decomposition(dec_value) {
+                    continue;
+                }
+
+                // не учитываем однобайтовый вариант, учитываем, что последовательность валидна
+                let width: u8 = [2, 2, 3, 4][((first >> 4) & 3) as usize];
+
+                if !iter.at_breakpoint(width as isize) {
+                    write_str(result, iter.block_slice(width as isize));
+                }
+
+                break (dec_value, code);
+            })
+        }
+    };
+}
+
+impl<'a> DecomposingNormalizer<'a>
+{
+    #[cfg(feature = "nfd")]
+    normalizer_methods!(normalize_nfd, forward_nfd, fast_forward_nfd, 0xC3);
+    #[cfg(feature = "nfkd")]
+    normalizer_methods!(normalize_nfkd, forward_nfkd, fast_forward_nfkd, 0xC2);
+
+    /// нормализация строки
+    /// исходная строка должна являться well-formed UTF-8 строкой
+    pub fn normalize(&self, input: &str) -> String
+    {
+        let mut result = String::with_capacity(input.len());
+
+        self.normalize_into(input, &mut result, &mut Vec::with_capacity(18));
+
+        result
+    }
+
+    /// нормализация строки с дописыванием результата в приёмник
+    pub fn normalize_to<S: NormalizeSink>(&self, input: &str, sink: &mut S)
+        -> Result<(), S::Error>
+    {
+        let mut output = SinkOutput::new(sink);
+
+        self.normalize_into(input, &mut output, &mut Vec::with_capacity(18));
+
+        output.finish()
+    }
+
+    /// нормализация строки с дописыванием в результат. буфер должен быть пуст
+    #[inline(never)]
+    // без форм NFD и NFKD нормализатор создать нельзя, и метод недостижим
+    #[cfg_attr(
+        not(any(feature = "nfd", feature = "nfkd")),
+        allow(unreachable_code, unused_variables, clippy::ptr_arg)
+    )]
+    fn normalize_into(&self, input: &str, result: &mut impl Output, buffer: &mut Vec<Codepoint>)
+    {
+        match self.form() {
+            #[cfg(feature = "nfd")]
+            DecomposingForm::Nfd => self.normalize_nfd(input, result, buffer),
+            #[cfg(feature = "nfkd")]
+            DecomposingForm::Nfkd => self.normalize_nfkd(input, result, buffer),
+        }
+    }
+
+    /// NFD или NFKD нормализация?
+    #[inline(never)]
+    fn form(&self) -> DecomposingForm
+    {
+        self.form
+    }
+
+    /// данные о декомпозиции символа
+    #[cfg(any(feature = "nfd", feature = "nfkd"))]
+    #[inline(always)]
+    fn get_decomposition_value(&self, code: u32) -> u32
+    {
+        get_decomposition_value(self.index, self.data, self.continuous_block_end, code)
+    }
+
+    /// кодпоинт - нестартер или имеет декомпозицию: пишем декомпозицию, нестартеры добавляем в буфер
+    #[cfg(any(feature = "nfd", feature = "nfkd"))]
+    #[inline(always)]
+    fn handle_dec_value(
+        &self,
+        dec_value: u32,
+        code: u32,
+        result: &mut impl Output,
+        buffer: &mut Vec<Codepoint>,
+    )
+    {
+        let marker = (dec_value as u8) >> 1;
+
+        // MARKER_STARTER | MARKER_COMBINES_BACKWARDS - стартеры без декомпозиции, сюда не попадают
+
+        match marker {
+            MARKER_NONSTARTER => {
+                buffer.push(Codepoint::from_code_and_ccc(code, (dec_value >> 8) as u8))
+            }
+            MARKER_SINGLETON => {
+                sort_and_write(result, buffer);
+                write_char(result, dec_value >> 8);
+            }
+            MARKER_EXPANSION => {
+                self.handle_expansion(dec_value, dec_value >> 18, result, buffer);
+            }
+            MARKER_EXPANSION_COMBINED_PATCH | MARKER_EXPANSION_COMBINED_EMPTY => {
+                // первый элемент - информация о комбинировании для NF(K)C, декомпозиция следует за ним
+                self.handle_expansion(dec_value, (dec_value >> 18) + 1, result, buffer);
+            }
+            MARKER_HANGUL_SYLLABLE => {
+                sort_and_write(result, buffer);
+                write_hangul_decomposition(result, code);
+            }
+            _ => {
+                // пара: стартер + нестартер
+                sort_and_write(result, buffer);
+
+                let starter = ((dec_value as u16) >> 1) as u32;
+                let nonstarter = dec_value >> 16;
+                let nonstarter_ccc = (self.get_decomposition_value(nonstarter) >> 8) as u8;
+
+                write_char(result, starter);
+                buffer.push(Codepoint::from_code_and_ccc(nonstarter, nonstarter_ccc));
+            }
+        }
+    }
+
+    /// декомпозиция, вынесенная во внешний блок: сначала стартеры (если есть), потом нестартеры (если есть)
+    #[cfg(any(feature = "nfd", feature = "nfkd"))]
+    #[inline(never)]
+    fn handle_expansion(
+        &self,
+        dec_value: u32,
+        index: u32,
+        result: &mut impl Output,
+        buffer: &mut Vec<Codepoint>,
+    )
+    {
+        let last_starter = (dec_value >> 8) & 0x1F;
+        let count = (dec_value >> 13) & 0x1F;
+
+        let expansions = &self.expansions[index as usize .. (index + count) as usize];
+
+        // декомпозиция может состоять только из нестартеров
+        let nonstarters = match expansions[0] as u8 == 0 {
+            true => {
+                sort_and_write(result, buffer);
+
+                expansions[..= last_starter as usize]
+                    .iter()
+                    .for_each(|&entry| write_char(result, entry >> 8));
+
+                &expansions[last_starter as usize + 1 ..]
+            }
+            false => expansions,
+        };
+
+        nonstarters
+            .iter()
+            .for_each(|&entry| buffer.push(Codepoint::from_baked(entry)));
+    }
+
+    /// NFD-нормализатор
+    #[cfg(feature = "nfd")]
+    pub const fn new_nfd() -> DecomposingNormalizer<'static>
+    {
+        NormalizationData::baked().nfd()
+    }
+
+    /// NFKD-нормализатор
+    #[cfg(feature = "nfkd")]
+    pub const fn new_nfkd() -> DecomposingNormalizer<'static>
+    {
+        NormalizationData::baked().nfkd()
+    }
+
+    /// NFD-нормализатор с данными заданной версии Unicode
+    #[cfg(feature = "nfd")]
+    pub const fn new_nfd_with(
+        version: UnicodeVersion,
+    ) -> Result<DecomposingNormalizer<'static>, DataError>
+    {
+        match NormalizationData::baked_with(version) {
+            Ok(data) => Ok(data.nfd()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// NFKD-нормализатор с данными заданной версии Unicode
+    #[cfg(feature = "nfkd")]
+    pub const fn new_nfkd_with(
+        version: UnicodeVersion,
+    ) -> Result<DecomposingNormalizer<'static>, DataError>
+    {
+        match NormalizationData::baked_with(version) {
+            Ok(data) => Ok(data.nfkd()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// версия Unicode, из которой запечены данные нормализатора
+    pub const fn unicode_version(&self) -> UnicodeVersion
+    {
+        self.unicode_version
+    }
+
+    /// заранее подготовленные данные. данные не копируются и не проверяются: они должны быть
+    /// запечены bakery, данные из другого источника передаются в try_from_baked
+    ///
+    /// # Panics
+    ///
+    /// паника (в const-контексте - ошибка компиляции), если в таблице быстрой проверки
+    /// quick_check не 128 элементов или в quick_check_index не 2048. другие ошибки в данных
+    /// не обнаруживаются: нормализация может паниковать или записать в результат недопустимые
+    /// кодпоинты. паника также, если форма (NFD - is_canonical, иначе NFKD) отключена возможностями крейта
+    pub const fn from_baked(
+        decomposition_data: data::DecompositionData<'a>,
+        is_canonical: bool,
+    ) -> Self
+    {
+        let form = match DecomposingForm::from_canonical(is_canonical) {
+            Ok(form) => form,
+            Err(_) => panic!("normalization form is disabled by crate features"),
+        };
+
+        Self {
+            index: decomposition_data.index,
+            data: decomposition_data.data,
+            expansions: decomposition_data.expansions,
+            continuous_block_end: decomposition_data.continuous_block_end,
+            quick_check: quick_check_table(&decomposition_data, false),
+            quick_check_index: quick_check_index(&decomposition_data, false),
+            quick_check_rows: decomposition_data.quick_check_rows,
+            form,
+            unicode_version: decomposition_data.unicode_version,
+        }
+    }
+}
+
+#[cfg(feature = "nfd")]
+static NFD: DecomposingNormalizer<'static> = DecomposingNormalizer::new_nfd();
+#[cfg(feature = "nfkd")]
+static NFKD: DecomposingNormalizer<'static> = DecomposingNormalizer::new_nfkd();
+
+impl DecomposingNormalizer<'static>
+{
+    /// общий NFD-нормализатор
+    #[cfg(feature = "nfd")]
+    pub const fn nfd() -> &'static DecomposingNormalizer<'static>
+    {
+        &NFD
+    }
+
+    /// общий NFKD-нормализатор
+    #[cfg(feature = "nfkd")]
+    pub const fn nfkd() -> &'static DecomposingNormalizer<'static>
+    {
+        &NFKD
+    }
+}
+
+/// по умолчанию - NFD
+#[cfg(feature = "nfd")]
+impl Default for DecomposingNormalizer<'static>
+{
+    fn default() -> Self
+    {
+        Self::new_nfd()
+    }
+}
+
+/// стартер без декомпозиции (MARKER_STARTER или MARKER_COMBINES_BACKWARDS)?
+/// у пары в этих битах хранится код стартера, который всегда больше 0x7
+#[cfg(any(feature = "nfd", feature = "nfkd"))]
+#[inline(always)]
+fn is_starter_without_decomposition(dec_value: u32) -> bool
+{
+    dec_value & 0xFC == 0
+}
+
+/// отсортировать нестартеры буфера по CCC и записать их
+#[cfg(any(feature = "nfd", feature = "nfkd"))]
+#[inline(always)]
+fn sort_and_write(result: &mut impl Output, buffer: &mut Vec<Codepoint>)
+{
+    if buffer.len() > 1 {
+        buffer.sort_by_key(|c| c.ccc());
+    }
+
+    buffer.iter().for_each(|c| result.push(char::from(*c)));
+    buffer.clear();
+}
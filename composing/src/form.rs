@@ -0,0 +1,62 @@
+use crate::binary::DataError;
+
+/// форма нормализации с композицией. варианты форм, отключённых возможностями крейта, отсутствуют,
+/// поэтому тип не публичный: иначе включение формы другим крейтом ломало бы исчерпывающий match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ComposingForm
+{
+    /// NFC
+    #[cfg(feature = "nfc")]
+    Nfc,
+    /// NFKC
+    #[cfg(feature = "nfkc")]
+    Nfkc,
+}
+
+impl ComposingForm
+{
+    /// NFC (is_canonical) или NFKC, если форма включена возможностью крейта
+    pub(crate) const fn from_canonical(is_canonical: bool) -> Result<Self, DataError>
+    {
+        match is_canonical {
+            #[cfg(feature = "nfc")]
+            true => Ok(Self::Nfc),
+            #[cfg(not(feature = "nfc"))]
+            true => Err(DataError::FormDisabled("NFC")),
+            #[cfg(feature = "nfkc")]
+            false => Ok(Self::Nfkc),
+            #[cfg(not(feature = "nfkc"))]
+            false => Err(DataError::FormDisabled("NFKC")),
+        }
+    }
+}
+
+/// форма нормализации без композиции. варианты форм, отключённых возможностями крейта, отсутствуют
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum DecomposingForm
+{
+    /// NFD
+    #[cfg(feature = "nfd")]
+    Nfd,
+    /// NFKD
+    #[cfg(feature = "nfkd")]
+    Nfkd,
+}
+
+impl DecomposingForm
+{
+    /// NFD (is_canonical) или NFKD, если форма включена возможностью крейта
+    pub(crate) const fn from_canonical(is_canonical: bool) -> Result<Self, DataError>
+    {
+        match is_canonical {
+            #[cfg(feature = "nfd")]
+            true => Ok(Self::Nfd),
+            #[cfg(not(feature = "nfd"))]
+            true => Err(DataError::FormDisabled("NFD")),
+            #[cfg(feature = "nfkd")]
+            false => Ok(Self::Nfkd),
+            #[cfg(not(feature = "nfkd"))]
+            false => Err(DataError::FormDisabled("NFKD")),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use core::convert::Infallible;
+use core::fmt;
+
+use crate::buffer::{InlineBuffer, INLINE_BUFFER_CAPACITY};
+use crate::sink::{NormalizeSink, SinkOutput};
+use crate::ComposingNormalizer;
+
+/// ошибка нормализации в буфер фиксированного размера
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntoSliceError
+{
+    /// результат не помещается в буфер, required - его полная длина в байтах
+    NeedMore
+    {
+        required: usize
+    },
+    /// последовательность комбинируемых кодпоинтов длиннее INLINE_BUFFER_CAPACITY
+    /// (текст не соответствует формату Stream-Safe Text)
+    SequenceTooLong,
+}
+
+impl fmt::Display for IntoSliceError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self {
+            Self::NeedMore { required } => {
+                write!(f, "output buffer is too small, {} bytes required", required)
+            }
+            Self::SequenceTooLong => write!(
+                f,
+                "combining sequence is longer than {} code points",
+                INLINE_BUFFER_CAPACITY
+            ),
+        }
+    }
+}
+
+impl core::error::Error for IntoSliceError {}
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// нормализация строки в переданный буфер без обращения к куче, возвращает длину результата.
+    /// если результат не поместился - возвращается его полная длина, содержимое буфера не определено
+    pub fn normalize_into_slice(&self, input: &str, out: &mut [u8])
+        -> Result<usize, IntoSliceError>
+    {
+        let mut sink = SliceSink { out, length: 0 };
+        let mut buffer = InlineBuffer::new();
+
+        let mut output = SinkOutput::new(&mut sink);
+        self.normalize_into(input, &mut output, &mut buffer);
+
+        match output.finish() {
+            Ok(()) => (),
+            Err(error) => match error {},
+        }
+
+        if buffer.is_overflowed() {
+            return Err(IntoSliceError::SequenceTooLong);
+        }
+
+        match sink.length <= sink.out.len() {
+            true => Ok(sink.length),
+            false => Err(IntoSliceError::NeedMore {
+                required: sink.length,
+            }),
+        }
+    }
+}
+
+/// запись в буфер фиксированного размера. после переполнения продолжаем считать длину результата
+struct SliceSink<'o>
+{
+    out: &'o mut [u8],
+    length: usize,
+}
+
+impl<'o> NormalizeSink for SliceSink<'o>
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn write_str(&mut self, string: &str) -> Result<(), Infallible>
+    {
+        let end = self.length + string.len();
+
+        if end <= self.out.len() {
+            self.out[self.length .. end].copy_from_slice(string.as_bytes());
+        }
+
+        self.length = end;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+use std::io::{self, BufRead, Read, Write};
+
+use crate::{ComposingNormalizer, StreamingNormalizer};
+
+/// нормализующая обёртка над источником данных. невалидный UTF-8 - ошибка io::ErrorKind::InvalidData
+pub struct NormalizingReader<'n, 'a, R: BufRead>
+{
+    inner: R,
+    stream: StreamingNormalizer<'n, 'a>,
+    decoder: Utf8Decoder,
+    /// нормализованные данные и количество уже прочитанных из них байт
+    output: String,
+    position: usize,
+    /// источник исчерпан
+    finished: bool,
+}
+
+impl<'n, 'a, R: BufRead> NormalizingReader<'n, 'a, R>
+{
+    /// нормализующий источник
+    pub fn new(normalizer: &'n ComposingNormalizer<'a>, inner: R) -> Self
+    {
+        Self {
+            inner,
+            stream: StreamingNormalizer::new(normalizer),
+            decoder: Utf8Decoder::default(),
+            output: String::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+
+    /// исходный источник
+    pub fn into_inner(self) -> R
+    {
+        self.inner
+    }
+}
+
+impl<'n, 'a, R: BufRead> Read for NormalizingReader<'n, 'a, R>
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>
+    {
+        let available = self.fill_buf()?;
+        let length = available.len().min(buf.len());
+
+        buf[.. length].copy_from_slice(&available[.. length]);
+        self.consume(length);
+
+        Ok(length)
+    }
+}
+
+impl<'n, 'a, R: BufRead> BufRead for NormalizingReader<'n, 'a, R>
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]>
+    {
+        // нормализация может придержать всё прочитанное, поэтому читаем, пока не получим результат
+        while self.position == self.output.len() && !self.finished {
+            self.output.clear();
+            self.position = 0;
+
+            let bytes = self.inner.fill_buf()?;
+
+            if bytes.is_empty() {
+                self.decoder.finish()?;
+                self.stream.finish_into(&mut self.output);
+                self.finished = true;
+                break;
+            }
+
+            let consumed = self
+                .decoder
+                .decode(bytes, &mut self.stream, &mut self.output)?;
+
+            self.inner.consume(consumed);
+        }
+
+        Ok(&self.output.as_bytes()[self.position ..])
+    }
+
+    fn consume(&mut self, amt: usize)
+    {
+        self.position = (self.position + amt).min(self.output.len());
+    }
+}
+
+/// нормализующая обёртка над приёмником данных. невалидный UTF-8 - ошибка io::ErrorKind::InvalidData.
+/// окончание текста придерживается до вызова finish. если обёртка удалена без finish, окончание
+/// записывается при удалении, как в BufWriter: ошибки при этом игнорируются, поэтому для их
+/// получения следует вызвать finish
+pub struct NormalizingWriter<'n, 'a, W: Write>
+{
+    /// исходный приёмник, забирается в finish
+    inner: Option<W>,
+    stream: StreamingNormalizer<'n, 'a>,
+    decoder: Utf8Decoder,
+    /// нормализованные данные перед записью в приёмник
+    output: String,
+}
+
+impl<'n, 'a, W: Write> NormalizingWriter<'n, 'a, W>
+{
+    /// нормализующий приёмник
+    pub fn new(normalizer: &'n ComposingNormalizer<'a>, inner: W) -> Self
+    {
+        Self {
+            inner: Some(inner),
+            stream: StreamingNormalizer::new(normalizer),
+            decoder: Utf8Decoder::default(),
+            output: String::new(),
+        }
+    }
+
+    /// записать придержанное окончание текста, вернуть исходный приёмник
+    pub fn finish(mut self) -> io::Result<W>
+    {
+        self.write_ending()?;
+
+        Ok(self.inner.take().unwrap())
+    }
+
+    /// записать придержанное окончание текста
+    fn write_ending(&mut self) -> io::Result<()>
+    {
+        let inner = match self.inner.as_mut() {
+            Some(inner) => inner,
+            None => return Ok(()),
+        };
+
+        self.decoder.finish()?;
+
+        self.output.clear();
+        self.stream.finish_into(&mut self.output);
+
+        inner.write_all(self.output.as_bytes())?;
+        inner.flush()
+    }
+}
+
+impl<'n, 'a, W: Write> Write for NormalizingWriter<'n, 'a, W>
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+    {
+        self.output.clear();
+
+        let consumed = self
+            .decoder
+            .decode(buf, &mut self.stream, &mut self.output)?;
+
+        self.inner
+            .as_mut()
+            .unwrap()
+            .write_all(self.output.as_bytes())?;
+
+        Ok(consumed)
+    }
+
+    /// записывается только окончательный результат: окончание текста, которое может измениться
+    /// при получении следующих данных, остаётся придержанным
+    fn flush(&mut self) -> io::Result<()>
+    {
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+impl<'n, 'a, W: Write> Drop for NormalizingWriter<'n, 'a, W>
+{
+    /// окончание, не записанное через finish. при панике не пишем - как и BufWriter
+    fn drop(&mut self)
+    {
+        if !std::thread::panicking() {
+            let _ = self.write_ending();
+        }
+    }
+}
+
+/// декодирование UTF-8 из байтов, поступающих частями
+#[derive(Default)]
+struct Utf8Decoder
+{
+    /// начало UTF-8 последовательности, не поместившейся в предыдущую часть
+    incomplete: [u8; 4],
+    length: usize,
+}
+
+impl Utf8Decoder
+{
+    /// нормализовать валидное начало части, вернуть количество обработанных байт.
+    /// ошибка возвращается, только если часть начинается с невалидной последовательности
+    fn decode(
+        &mut self,
+        bytes: &[u8],
+        stream: &mut StreamingNormalizer,
+        result: &mut String,
+    ) -> io::Result<usize>
+    {
+        let mut consumed = 0;
+
+        // дополняем последовательность, начатую в предыдущей части
+        if self.length != 0 {
+            let width = utf8_width(self.incomplete[0]);
+            let count = (width - self.length).min(bytes.len());
+
+            self.incomplete[self.length .. self.length + count].copy_from_slice(&bytes[.. count]);
+            self.length += count;
+            consumed = count;
+
+            if self.length < width {
+                return Ok(consumed);
+            }
+
+            self.length = 0;
+
+            match core::str::from_utf8(&self.incomplete[.. width]) {
+                Ok(str) => stream.push_into(str, result),
+                Err(_) => return Err(invalid_data()),
+            }
+        }
+
+        let bytes = &bytes[consumed ..];
+
+        match core::str::from_utf8(bytes) {
+            Ok(str) => {
+                stream.push_into(str, result);
+                consumed += bytes.len();
+            }
+            Err(error) => {
+                let valid = error.valid_up_to();
+
+                // SAFETY: начало до valid_up_to - валидный UTF-8
+                stream.push_into(
+                    unsafe { core::str::from_utf8_unchecked(&bytes[.. valid]) },
+                    result,
+                );
+                consumed += valid;
+
+                match error.error_len() {
+                    // часть обрывается на середине последовательности
+                    None => {
+                        let rest = &bytes[valid ..];
+
+                        self.incomplete[.. rest.len()].copy_from_slice(rest);
+                        self.length = rest.len();
+                        consumed += rest.len();
+                    }
+                    // невалидную последовательность вернём ошибкой при следующем вызове
+                    Some(_) => {
+                        if consumed == 0 {
+                            return Err(invalid_data());
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(consumed)
+    }
+
+    /// данные закончились - последовательность не должна быть оборвана
+    fn finish(&mut self) -> io::Result<()>
+    {
+        match self.length {
+            0 => Ok(()),
+            _ => {
+                self.length = 0;
+                Err(invalid_data())
+            }
+        }
+    }
+}
+
+/// длина UTF-8 последовательности по первому (не ASCII) байту
+#[inline(always)]
+fn utf8_width(first: u8) -> usize
+{
+    match first {
+        0xF0 .. => 4,
+        0xE0 .. => 3,
+        _ => 2,
+    }
+}
+
+/// ошибка - невалидный UTF-8
+fn invalid_data() -> io::Error
+{
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8",
+    )
+}
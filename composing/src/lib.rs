@@ -1,25 +1,76 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "nfc", feature = "nfkc", feature = "nfd", feature = "nfkd")))]
+compile_error!("at least one normalization form feature is required: nfc, nfkc, nfd or nfkd");
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+pub use binary::DataError;
+use buffer::{Buffer, InlineBuffer};
 pub use codepoint::Codepoint;
 use composition::combine_and_write;
 use composition::combine_and_write_hangul_vt;
 use composition::combine_backwards;
 use composition::Combining;
-pub use data::{CompositionData, DecompositionData};
-use slice::aligned::Aligned;
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
+use composition::{compose_hangul_lv, compose_hangul_lvt};
+pub use data::{CompositionData, DecompositionData, ExpansionsPatch, NormalizationData};
+pub use decomposing::DecomposingNormalizer;
+use form::{ComposingForm, DecomposingForm};
+pub use into_slice::IntoSliceError;
+#[cfg(feature = "std")]
+pub use io::{NormalizingReader, NormalizingWriter};
+pub use normalize_iter::NormalizeIter;
+pub use quick_check::IsNormalized;
+#[cfg(feature = "std")]
+pub use sink::IoSink;
+use sink::{CompareOutput, Output, SinkOutput};
+pub use sink::{FmtSink, NormalizeSink};
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
 use slice::iter::CharsIter;
-
+pub use streaming::StreamingNormalizer;
+pub use utf16::{UnpairedSurrogates, Utf16NormalizeError};
+pub use utf32::CodepointNormalizeError;
+pub use utf8::Utf8NormalizeError;
+pub use version::UnicodeVersion;
+
+mod binary;
+mod buffer;
 mod codepoint;
 mod composition;
 mod data;
+mod decomposing;
+mod form;
+mod into_slice;
+#[cfg(feature = "std")]
+mod io;
+mod normalize_iter;
+mod quick_check;
+mod sink;
 mod slice;
-
-/// последний кодпоинт с декомпозицией (U+2FA1D), его блок - 0x5F4
-pub const LAST_DECOMPOSING_CODEPOINT_BLOCK: u16 = (0x2FA1D >> (18 - 11)) as u16;
+mod streaming;
+mod utf16;
+mod utf32;
+mod utf8;
+mod validation;
+mod version;
+
+/// последний кодпоинт с декомпозицией (U+2FA1D) в данных последней версии Unicode
+const LAST_DECOMPOSING_CODEPOINT: u32 =
+    include!("./../../data/15.1.0/last_decomposing_codepoint.txt");
+/// блок последнего кодпоинта с декомпозицией (0x5F4)
+pub const LAST_DECOMPOSING_CODEPOINT_BLOCK: u16 = (LAST_DECOMPOSING_CODEPOINT >> (18 - 11)) as u16;
 
 /// стартер без декомпозиции
 pub const MARKER_STARTER: u8 = 0b_000;
 /// маркер композиции с предыдущим кодпоинтом (в том числе чамо хангыль)
 pub const MARKER_COMBINES_BACKWARDS: u8 = 0b_001;
-/// нестартер без декомпозиции
+/// нестартер без декомпозиции: биты 8 .. 16 - CCC, бит 16 - нестартер может скомбинироваться
+/// с предыдущим кодпоинтом (NF(K)C_QC = Maybe)
 pub const MARKER_NONSTARTER: u8 = 0b_010;
 /// синглтон
 pub const MARKER_SINGLETON: u8 = 0b_011;
@@ -33,58 +84,80 @@ pub const MARKER_EXPANSION_COMBINED_EMPTY: u8 = 0b_110;
 /// слог хангыль
 pub const MARKER_HANGUL_SYLLABLE: u8 = 0b_111;
 
-/// нормализатор NF(K)C
+/// нормализатор NF(K)C. данные не копируются, поэтому клонирование дёшево
+#[derive(Clone)]
 #[repr(C, align(16))]
 pub struct ComposingNormalizer<'a>
 {
     /// основные данные
-    data: Aligned<'a, u32>,
+    data: &'a [u32],
     /// индекс блока. u8 достаточно, т.к. в NFC последний блок - 0x40, в NFKC - 0x6F (+1 для пустого блока)
-    index: Aligned<'a, u16>,
+    index: &'a [u16],
     /// композиции
-    compositions: Aligned<'a, u64>,
+    compositions: data::CompositionData<'a>,
     /// данные кодпоинтов, которые не вписываются в основную часть
-    expansions: Aligned<'a, u32>,
+    expansions: &'a [u32],
     /// с U+0000 и до этого кодпоинта включительно блоки в data идут последовательно
     continuous_block_end: u32,
     /// патч декомпозиций
-    expansions_patch: Aligned<'a, u32>,
+    expansions_patch: &'a [u32],
+    /// 2- и 3-байтовые последовательности, прошедшие быструю проверку, по первым двум байтам.
+    /// запечённая таблица формы - не копируется в каждый нормализатор
+    quick_check: &'a [u64; 64],
+    /// номера строк быстрой проверки отдельных 3-байтовых последовательностей формы
+    quick_check_index: &'a [u16; 1024],
+    /// строки быстрой проверки 3-байтовых последовательностей
+    quick_check_rows: &'a [u64],
     /// NFC или NFKC
-    is_canonical: bool,
+    form: ComposingForm,
+    /// версия Unicode данных
+    unicode_version: UnicodeVersion,
 }
 
 // методы нормализации вынесены в макрос в целях оптимизации
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
 macro_rules! normalizer_methods {
     ($normalize_method:ident, $forward:ident, $fast_forward:ident, $first_code_boundary:expr) => {
+        /// CHUNK - строка является частью потока: буфер в конце строки не комбинируется, а остаётся
+        /// вместе с combining до следующей части. возвращает, прошёл ли быструю проверку последний
+        /// записанный кодпоинт (аналог passed в normalize_codepoint)
         #[inline(always)]
-        fn $normalize_method(&self, input: &str) -> String
+        fn $normalize_method<const CHUNK: bool>(
+            &self,
+            input: &str,
+            result: &mut impl Output,
+            buffer: &mut impl Buffer,
+            combining: &mut Combining,
+        ) -> bool
         {
-            let mut result = String::with_capacity(input.len());
-            let mut buffer = Vec::with_capacity(18);
             let iter = &mut CharsIter::new(input);
-            let mut combining = Combining::None;
 
             loop {
+                // приёмник вернул ошибку - остаток строки не нормализуем
+                if result.is_stopped() {
+                    return false;
+                }
+
                 let entry = match !buffer.is_empty() {
-                    true => match self.$forward(iter, &mut combining, &mut result, &mut buffer) {
-                        Some(entry) => Some(entry),
-                        None => continue,
-                    },
-                    false => self.$fast_forward(iter, &mut combining, &mut result, &mut buffer),
+                    true => {
+                        if CHUNK && iter.is_empty() {
+                            return false;
+                        }
+
+                        match self.$forward(iter, combining, result, buffer) {
+                            Some(entry) => Some(entry),
+                            None => continue,
+                        }
+                    }
+                    false => self.$fast_forward(iter, combining, result, buffer),
                 };
 
                 match entry {
                     Some((dec_value, code)) => {
-                        self.handle_dec_value(
-                            dec_value,
-                            code,
-                            &mut combining,
-                            &mut result,
-                            &mut buffer,
-                        );
+                        self.handle_dec_value(dec_value, code, combining, result, buffer);
                         iter.set_breakpoint();
                     }
-                    None => return result,
+                    None => return !iter.at_breakpoint(0),
                 }
             }
         }
@@ -96,8 +169,8 @@ macro_rules! normalizer_methods {
             &self,
             iter: &mut CharsIter,
             combining: &mut Combining,
-            result: &mut String,
-            buffer: &mut Vec<Codepoint>,
+            result: &mut impl Output,
+            buffer: &mut impl Buffer,
         ) -> Option<(u32, u32)>
         {
             iter.set_breakpoint();
@@ -125,8 +198,8 @@ macro_rules! normalizer_methods {
             &self,
             iter: &mut CharsIter,
             combining: &mut Combining,
-            result: &mut String,
-            buffer: &mut Vec<Codepoint>,
+            result: &mut impl Output,
+            buffer: &mut impl Buffer,
         ) -> Option<(u32, u32)>
         {
             Some(loop {
@@ -141,7 +214,30 @@ macro_rules! normalizer_methods {
                 // текст, состоящий только из ASCII-символов уже NF(K)C нормализован
                 // учитывая то, что для NFC и NFKC символы до U+0300 и U+00A0 соответственно также нормализованы,
                 // используем не 0x80 в качестве границы, а значение первого байта UTF-8 вышеуказанных символов.
-                if first < $first_code_boundary {
+                // 2-байтовые последовательности проверяем по битовой карте, без декодирования. за такими
+                // байтами обычно идут такие же - пропускаем их блоками, вместе с 3-байтовыми из блоков,
+                // целиком прошедших проверку
+                if first < $first_code_boundary
+                    || first < 0xE0
+                        && passes_quick_check(self.quick_check, first, unsafe {
+                            iter.peek_unchecked()
+                        })
+                {
+                    iter.skip_passing($first_code_boundary, self.quick_check);
+                    continue;
+                }
+
+                // отдельные 3-байтовые последовательности (CJK после знаков препинания, деванагари
+                // в блоке с нестартерами) проверяем по строкам, также без декодирования
+                if first & 0xF0 == 0xE0
+                    && passes_quick_check_row(
+                        self.quick_check_index,
+                        self.quick_check_rows,
+                        first,
+                        unsafe { iter.peek_two_unchecked() },
+                    )
+                {
+                    unsafe { iter.skip_unchecked(2) };
                     continue;
                 }
 
@@ -154,6 +250,18 @@ macro_rules! normalizer_methods {
                     continue;
                 }
 
+                // гласная чамо после ведущей согласной, пропущенной в быстром цикле, - комбинируем
+                // последовательность слогов хангыль целиком, не возвращаясь к записанному результату.
+                // итератор передаётся копией: ссылка на него не должна покидать цикл, иначе указатель
+                // итератора не остаётся в регистре и сохраняется в память на каждом символе
+                if (dec_value as u8) >> 1 == MARKER_COMBINES_BACKWARDS {
+                    if let Some(composed) = compose_hangul_syllables(iter.clone(), code, result) {
+                        *iter = composed;
+                        *combining = Combining::None;
+                        continue;
+                    }
+                }
+
                 // выходим из быстрого цикла, т.к. мы столкнулись с ситуацией, когда требуется
                 // декомпозиция / комбинирование
 
@@ -194,50 +302,98 @@ macro_rules! normalizer_methods {
 
 impl<'a> ComposingNormalizer<'a>
 {
+    #[cfg(feature = "nfc")]
     normalizer_methods!(normalize_nfc, forward_nfc, fast_forward_nfc, 0xCC);
+    #[cfg(feature = "nfkc")]
     normalizer_methods!(normalize_nfkc, forward_nfkc, fast_forward_nfkc, 0xC2);
 
     /// нормализация строки
     /// исходная строка должна являться well-formed UTF-8 строкой
-    #[inline(never)]
     pub fn normalize(&self, input: &str) -> String
     {
-        match self.is_canonical() {
-            true => self.normalize_nfc(input),
-            false => self.normalize_nfkc(input),
+        let mut result = String::with_capacity(input.len());
+
+        self.normalize_into(input, &mut result, &mut Vec::with_capacity(18));
+
+        result
+    }
+
+    /// нормализация строки без копирования, если строка уже нормализована. строка проверяется
+    /// и нормализуется за один проход, её начало копируется только при первом изменении
+    pub fn normalize_cow<'s>(&self, input: &'s str) -> Cow<'s, str>
+    {
+        let mut output = CompareOutput::copying(input);
+        let mut buffer = InlineBuffer::new();
+
+        self.normalize_into(input, &mut output, &mut buffer);
+
+        // последовательность комбинируемых кодпоинтов длиннее встроенного буфера
+        if buffer.is_overflowed() {
+            return Cow::Owned(self.normalize(input));
         }
+
+        output.into_cow()
     }
 
-    /// NFC или NFKC нормализация?
-    #[inline(never)]
-    fn is_canonical(&self) -> bool
+    /// нормализация строки с дописыванием результата в приёмник
+    pub fn normalize_to<S: NormalizeSink>(&self, input: &str, sink: &mut S)
+        -> Result<(), S::Error>
     {
-        self.is_canonical
+        let mut output = SinkOutput::new(sink);
+
+        self.normalize_into(input, &mut output, &mut Vec::with_capacity(18));
+
+        output.finish()
     }
 
-    /// данные о декомпозиции символа
-    #[inline(always)]
-    fn get_decomposition_value(&self, code: u32) -> u32
+    /// нормализация строки с дописыванием в результат. буфер должен быть пуст
+    #[inline(never)]
+    // без форм NFC и NFKC нормализатор создать нельзя, и метод недостижим
+    #[cfg_attr(
+        not(any(feature = "nfc", feature = "nfkc")),
+        allow(unreachable_code, unused_mut, unused_variables)
+    )]
+    fn normalize_into(&self, input: &str, result: &mut impl Output, buffer: &mut impl Buffer)
     {
-        let data_block_base = match code <= self.continuous_block_end {
-            true => 0x600 | (((code >> 3) as u16) & !0xF),
-            false => {
-                let group_index = (code >> 7) as u16;
-
-                // все кодпоинты, следующие за U+2FA1D не имеют декомпозиции
-                if group_index > LAST_DECOMPOSING_CODEPOINT_BLOCK {
-                    return 0;
-                };
+        let mut combining = Combining::None;
 
-                self.index[group_index as usize]
+        match self.form() {
+            #[cfg(feature = "nfc")]
+            ComposingForm::Nfc => {
+                self.normalize_nfc::<false>(input, result, buffer, &mut combining)
+            }
+            #[cfg(feature = "nfkc")]
+            ComposingForm::Nfkc => {
+                self.normalize_nfkc::<false>(input, result, buffer, &mut combining)
             }
         };
+    }
 
-        let code_offsets = (code as u16) & 0x7F;
-        let data_block_index = data_block_base | (code_offsets >> 3) as u16;
-        let index = self.index[data_block_index as usize] | code_offsets & 0x7;
+    /// NFC или NFKC нормализация?
+    #[inline(never)]
+    fn form(&self) -> ComposingForm
+    {
+        self.form
+    }
 
-        self.data[index as usize]
+    /// кодпоинт, начиная с которого требуется проверка
+    /// (кодпоинты до U+0300 для NFC и U+00A0 для NFKC нормализованы)
+    #[inline(always)]
+    fn first_codepoint_boundary(&self) -> u32
+    {
+        match self.form {
+            #[cfg(feature = "nfc")]
+            ComposingForm::Nfc => 0x300,
+            #[cfg(feature = "nfkc")]
+            ComposingForm::Nfkc => 0xA0,
+        }
+    }
+
+    /// данные о декомпозиции символа
+    #[inline(always)]
+    fn get_decomposition_value(&self, code: u32) -> u32
+    {
+        get_decomposition_value(self.index, self.data, self.continuous_block_end, code)
     }
 
     /// записать в буфер декомпозицию (точнее, прекомпозицию) последнего полученного кодпоинта
@@ -245,8 +401,8 @@ impl<'a> ComposingNormalizer<'a>
     #[inline(never)]
     fn buffer_previous(
         &self,
-        result: &mut String,
-        buffer: &mut Vec<Codepoint>,
+        result: &mut impl Output,
+        buffer: &mut impl Buffer,
         code: u32,
     ) -> Combining
     {
@@ -293,6 +449,46 @@ impl<'a> ComposingNormalizer<'a>
         }
     }
 
+    /// нормализация очередного кодпоинта, полученного не из UTF-8 строки.
+    /// passed - последний записанный в результат кодпоинт прошёл быструю проверку
+    /// (аналог того, что в быстром цикле указатель не находится на запомненной позиции)
+    #[inline(always)]
+    fn normalize_codepoint(
+        &self,
+        code: u32,
+        combining: &mut Combining,
+        passed: &mut bool,
+        result: &mut impl Output,
+        buffer: &mut impl Buffer,
+    )
+    {
+        let dec_value = match code < self.first_codepoint_boundary() {
+            true => 0,
+            false => self.get_decomposition_value(code),
+        };
+
+        if dec_value & 1 == 0 {
+            combine_and_write(result, buffer, *combining, &self.compositions);
+            write_char(result, code);
+
+            *passed = true;
+            return;
+        }
+
+        if *passed && buffer.is_empty() {
+            let marker = dec_value as u8 >> 1;
+
+            if marker == MARKER_NONSTARTER || marker == MARKER_EXPANSION {
+                let previous = u32::from(result.pop().unwrap());
+                *combining = self.buffer_previous(result, buffer, previous);
+            }
+        }
+
+        self.handle_dec_value(dec_value, code, combining, result, buffer);
+
+        *passed = false;
+    }
+
     /// кодпоинт участвует в декомпозиции - комбинируем текущий буфер (кроме случая с нестартером),
     /// пишем в буфер декомпозицию кодпоинта или комбинируем сразу (хангыль, комбинирование с предыдущим)
     #[inline(always)]
@@ -301,8 +497,8 @@ impl<'a> ComposingNormalizer<'a>
         dec_value: u32,
         code: u32,
         combining: &mut Combining,
-        result: &mut String,
-        buffer: &mut Vec<Codepoint>,
+        result: &mut impl Output,
+        buffer: &mut impl Buffer,
     )
     {
         let marker = (dec_value as u8) >> 1;
@@ -318,6 +514,13 @@ impl<'a> ComposingNormalizer<'a>
                 combine_and_write(result, buffer, *combining, &self.compositions);
 
                 let code = dec_value >> 8;
+
+                // чамо совместимости (NFKC), декомпозиция которых - гласная или завершающая согласная,
+                // комбинируются с предыдущим L / LV так же, как и сами V / T
+                if combine_and_write_hangul_vt(result, code, combining) {
+                    return;
+                }
+
                 buffer.push(Codepoint::from_code(code));
 
                 // в данном случае значение комбинирования нужно получить ещё раз заглянув в таблицу,
@@ -377,43 +580,82 @@ impl<'a> ComposingNormalizer<'a>
     }
 
     /// NFC-нормализатор
-    pub fn new_nfc() -> Self
+    #[cfg(feature = "nfc")]
+    pub const fn new_nfc() -> ComposingNormalizer<'static>
     {
-        Self::from_baked(
-            data::nfd(),
-            data::compositions(),
-            data::nfc_expansions(),
-            true,
-        )
+        NormalizationData::baked().nfc()
     }
 
     /// NFKC-нормализатор
-    pub fn new_nfkc() -> Self
+    #[cfg(feature = "nfkc")]
+    pub const fn new_nfkc() -> ComposingNormalizer<'static>
+    {
+        NormalizationData::baked().nfkc()
+    }
+
+    /// NFC-нормализатор с данными заданной версии Unicode
+    #[cfg(feature = "nfc")]
+    pub const fn new_nfc_with(
+        version: UnicodeVersion,
+    ) -> Result<ComposingNormalizer<'static>, DataError>
     {
-        Self::from_baked(
-            data::nfkd(),
-            data::compositions(),
-            data::nfkc_expansions(),
-            false,
-        )
+        match NormalizationData::baked_with(version) {
+            Ok(data) => Ok(data.nfc()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// NFKC-нормализатор с данными заданной версии Unicode
+    #[cfg(feature = "nfkc")]
+    pub const fn new_nfkc_with(
+        version: UnicodeVersion,
+    ) -> Result<ComposingNormalizer<'static>, DataError>
+    {
+        match NormalizationData::baked_with(version) {
+            Ok(data) => Ok(data.nfkc()),
+            Err(error) => Err(error),
+        }
     }
 
-    /// заранее подготовленные данные
-    pub fn from_baked(
-        decomposition_data: data::DecompositionData,
-        compositions: data::CompositionData,
-        expansions_patch: data::ExpansionsPatch,
+    /// версия Unicode, из которой запечены данные нормализатора
+    pub const fn unicode_version(&self) -> UnicodeVersion
+    {
+        self.unicode_version
+    }
+
+    /// заранее подготовленные данные. данные не копируются и не проверяются: они должны быть
+    /// запечены bakery, данные из другого источника передаются в try_from_baked
+    ///
+    /// # Panics
+    ///
+    /// паника (в const-контексте - ошибка компиляции), если в таблице быстрой проверки
+    /// quick_check не 128 элементов или в quick_check_index не 2048. другие ошибки в данных
+    /// не обнаруживаются: нормализация может паниковать или записать в результат недопустимые
+    /// кодпоинты. паника также, если форма (NFC - is_canonical, иначе NFKC) отключена возможностями крейта
+    pub const fn from_baked(
+        decomposition_data: data::DecompositionData<'a>,
+        compositions: data::CompositionData<'a>,
+        expansions_patch: data::ExpansionsPatch<'a>,
         is_canonical: bool,
     ) -> Self
     {
+        let form = match ComposingForm::from_canonical(is_canonical) {
+            Ok(form) => form,
+            Err(_) => panic!("normalization form is disabled by crate features"),
+        };
+
         Self {
-            index: Aligned::from(decomposition_data.index),
-            data: Aligned::from(decomposition_data.data),
-            expansions: Aligned::from(decomposition_data.expansions),
-            compositions: Aligned::from(compositions.compositions),
+            index: decomposition_data.index,
+            data: decomposition_data.data,
+            expansions: decomposition_data.expansions,
+            compositions,
             continuous_block_end: decomposition_data.continuous_block_end,
-            expansions_patch: Aligned::from(expansions_patch.expansions),
-            is_canonical,
+            expansions_patch: expansions_patch.expansions,
+            quick_check: quick_check_table(&decomposition_data, true),
+            quick_check_index: quick_check_index(&decomposition_data, true),
+            quick_check_rows: decomposition_data.quick_check_rows,
+            form,
+            unicode_version: decomposition_data.unicode_version,
         }
     }
 
@@ -422,8 +664,8 @@ impl<'a> ComposingNormalizer<'a>
     fn buffer_previous_expansion(
         &self,
         dec_value: u32,
-        result: &mut String,
-        buffer: &mut Vec<Codepoint>,
+        result: &mut impl Output,
+        buffer: &mut impl Buffer,
     ) -> Combining
     {
         let last_starter = (dec_value >> 8) & 0x1F;
@@ -448,8 +690,8 @@ impl<'a> ComposingNormalizer<'a>
     fn buffer_previous_expansion_patch(
         &self,
         dec_value: u32,
-        result: &mut String,
-        buffer: &mut Vec<Codepoint>,
+        result: &mut impl Output,
+        buffer: &mut impl Buffer,
     ) -> Combining
     {
         let index = dec_value >> 18;
@@ -478,8 +720,8 @@ impl<'a> ComposingNormalizer<'a>
         &self,
         dec_value: u32,
         combining: &mut Combining,
-        result: &mut String,
-        buffer: &mut Vec<Codepoint>,
+        result: &mut impl Output,
+        buffer: &mut impl Buffer,
     )
     {
         let last_starter = (dec_value >> 8) & 0x1F;
@@ -512,8 +754,8 @@ impl<'a> ComposingNormalizer<'a>
         &self,
         dec_value: u32,
         combining: &mut Combining,
-        result: &mut String,
-        buffer: &mut Vec<Codepoint>,
+        result: &mut impl Output,
+        buffer: &mut impl Buffer,
     )
     {
         let index = dec_value >> 18;
@@ -537,16 +779,203 @@ impl<'a> ComposingNormalizer<'a>
     }
 }
 
+#[cfg(feature = "nfc")]
+static NFC: ComposingNormalizer<'static> = ComposingNormalizer::new_nfc();
+#[cfg(feature = "nfkc")]
+static NFKC: ComposingNormalizer<'static> = ComposingNormalizer::new_nfkc();
+
+impl ComposingNormalizer<'static>
+{
+    /// общий NFC-нормализатор
+    #[cfg(feature = "nfc")]
+    pub const fn nfc() -> &'static ComposingNormalizer<'static>
+    {
+        &NFC
+    }
+
+    /// общий NFKC-нормализатор
+    #[cfg(feature = "nfkc")]
+    pub const fn nfkc() -> &'static ComposingNormalizer<'static>
+    {
+        &NFKC
+    }
+}
+
+/// по умолчанию - NFC
+#[cfg(feature = "nfc")]
+impl Default for ComposingNormalizer<'static>
+{
+    fn default() -> Self
+    {
+        Self::new_nfc()
+    }
+}
+
 /// дописать символ(по коду) в результат
 #[inline(always)]
-fn write_char(result: &mut String, code: u32)
+fn write_char(result: &mut impl Output, code: u32)
 {
     result.push(unsafe { char::from_u32_unchecked(code) });
 }
 
 /// дописать уже нормализованный кусок исходной строки в UTF-8 результат
 #[inline(always)]
-fn write_str(result: &mut String, string: &[u8])
+fn write_str(result: &mut impl Output, string: &[u8])
 {
     result.push_str(unsafe { core::str::from_utf8_unchecked(string) });
 }
+
+/// данные о декомпозиции символа из таблиц NF(K)D
+#[inline(always)]
+const fn get_decomposition_value(
+    index: &[u16],
+    data: &[u32],
+    continuous_block_end: u32,
+    code: u32,
+) -> u32
+{
+    let data_block_base = match code <= continuous_block_end {
+        true => 0x600 | (((code >> 3) as u16) & !0xF),
+        false => {
+            let group_index = (code >> 7) as u16;
+
+            // все кодпоинты, следующие за U+2FA1D не имеют декомпозиции
+            if group_index > LAST_DECOMPOSING_CODEPOINT_BLOCK {
+                return 0;
+            };
+
+            index[group_index as usize]
+        }
+    };
+
+    let code_offsets = (code as u16) & 0x7F;
+    let data_block_index = data_block_base | (code_offsets >> 3);
+    let index = index[data_block_index as usize] | code_offsets & 0x7;
+
+    data[index as usize]
+}
+
+/// слоги хангыль из чамо L V T?: первая гласная уже прочитана, ведущая согласная перед ней находится
+/// в текущем отрезке. отрезок до ведущей согласной и слоги пишутся в результат, пока за слогом
+/// следуют L V, позиция после последнего слога запоминается. возвращает итератор после слогов
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
+#[inline(never)]
+fn compose_hangul_syllables<'a>(
+    mut iter: CharsIter<'a>,
+    v: u32,
+    result: &mut impl Output,
+) -> Option<CharsIter<'a>>
+{
+    let mut lv = iter
+        .block_last_three_bytes(3)
+        .and_then(|l| compose_hangul_lv(l, v))?;
+
+    write_str(result, iter.block_slice(6));
+
+    loop {
+        let syllable = match iter
+            .peek_three_bytes(0)
+            .and_then(|t| compose_hangul_lvt(lv, t))
+        {
+            Some(lvt) => {
+                unsafe { iter.skip_unchecked(3) };
+                lvt
+            }
+            None => lv,
+        };
+
+        write_char(result, syllable);
+
+        // между слогами могут быть символы ASCII (пробелы, знаки препинания) - за ними следует
+        // стартер L, поэтому их можно записать сразу
+        let ascii = iter.peek_ascii();
+
+        lv = match iter
+            .peek_three_bytes(ascii)
+            .zip(iter.peek_three_bytes(ascii + 3))
+            .and_then(|(l, v)| compose_hangul_lv(l, v))
+        {
+            Some(lv) => {
+                if ascii != 0 {
+                    write_str(result, unsafe { iter.next_slice_unchecked(ascii) });
+                }
+
+                unsafe { iter.skip_unchecked(6) };
+                lv
+            }
+            None => break,
+        };
+    }
+
+    iter.set_breakpoint();
+
+    Some(iter)
+}
+
+/// таблица быстрой проверки формы из запечённых данных: последовательности UTF-8, прошедшие быструю
+/// проверку (NF(K)C, первая половина quick_check) или являющиеся стартерами без декомпозиции
+/// (NF(K)D, вторая половина)
+const fn quick_check_table<'a>(
+    data: &data::DecompositionData<'a>,
+    is_composing: bool,
+) -> &'a [u64; 64]
+{
+    assert!(
+        data.quick_check.len() == 128,
+        "quick check table must contain 128 elements"
+    );
+
+    let table = match is_composing {
+        true => data.quick_check.first_chunk::<64>(),
+        false => data.quick_check.last_chunk::<64>(),
+    };
+
+    match table {
+        Some(table) => table,
+        None => unreachable!(),
+    }
+}
+
+/// индекс строк быстрой проверки 3-байтовых последовательностей формы из запечённых данных
+/// (NF(K)C - первая половина quick_check_index, NF(K)D - вторая)
+const fn quick_check_index<'a>(
+    data: &data::DecompositionData<'a>,
+    is_composing: bool,
+) -> &'a [u16; 1024]
+{
+    assert!(
+        data.quick_check_index.len() == 2048,
+        "quick check index must contain 2048 elements"
+    );
+
+    let index = match is_composing {
+        true => data.quick_check_index.first_chunk::<1024>(),
+        false => data.quick_check_index.last_chunk::<1024>(),
+    };
+
+    match index {
+        Some(index) => index,
+        None => unreachable!(),
+    }
+}
+
+/// 3-байтовая последовательность прошла быструю проверку? первый байт уже прочитан
+#[inline(always)]
+fn passes_quick_check_row(
+    index: &[u16; 1024],
+    rows: &[u64],
+    first: u8,
+    (second, third): (u8, u8),
+) -> bool
+{
+    let row = index[((first & 0xF) as usize) << 6 | (second & 0x3F) as usize];
+
+    (rows[row as usize] >> (third & 0x3F)) & 1 != 0
+}
+
+/// последовательность UTF-8 прошла быструю проверку? первый байт - не ASCII
+#[inline(always)]
+fn passes_quick_check(blocks: &[u64; 64], first: u8, second: u8) -> bool
+{
+    (blocks[(first & 0x3F) as usize] >> (second & 0x3F)) & 1 != 0
+}
@@ -0,0 +1,82 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::codepoint::Codepoint;
+use crate::composition::{combine_and_write, Combining};
+use crate::ComposingNormalizer;
+
+/// итератор, лениво нормализующий последовательность символов
+pub struct NormalizeIter<'n, 'a, I: Iterator<Item = char>>
+{
+    normalizer: &'n ComposingNormalizer<'a>,
+    /// источник символов
+    source: I,
+    /// результат, ещё не отданный итератором
+    output: VecDeque<char>,
+    /// текущая последовательность комбинируемых кодпоинтов
+    buffer: Vec<Codepoint>,
+    /// комбинирование стартера буфера
+    combining: Combining,
+    /// последний кодпоинт результата прошёл быструю проверку
+    passed: bool,
+    /// источник исчерпан
+    finished: bool,
+}
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// ленивая нормализация последовательности символов. в памяти хранится только текущая
+    /// последовательность комбинируемых кодпоинтов
+    pub fn normalize_iter<I: Iterator<Item = char>>(&self, iter: I) -> NormalizeIter<'_, 'a, I>
+    {
+        NormalizeIter {
+            normalizer: self,
+            source: iter,
+            output: VecDeque::with_capacity(4),
+            buffer: Vec::with_capacity(18),
+            combining: Combining::None,
+            passed: false,
+            finished: false,
+        }
+    }
+}
+
+impl<'n, 'a, I: Iterator<Item = char>> Iterator for NormalizeIter<'n, 'a, I>
+{
+    type Item = char;
+
+    fn next(&mut self) -> Option<char>
+    {
+        // последний символ результата может быть забран обратно для комбинирования со следующим кодпоинтом,
+        // поэтому отдаём его только тогда, когда источник исчерпан
+        while self.output.len() < 2 && !self.finished {
+            match self.source.next() {
+                Some(c) => self.normalizer.normalize_codepoint(
+                    u32::from(c),
+                    &mut self.combining,
+                    &mut self.passed,
+                    &mut self.output,
+                    &mut self.buffer,
+                ),
+                None => {
+                    combine_and_write(
+                        &mut self.output,
+                        &mut self.buffer,
+                        self.combining,
+                        &self.normalizer.compositions,
+                    );
+
+                    self.finished = true;
+                }
+            }
+        }
+
+        self.output.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        // NFKC может увеличить длину строки в 18 раз, композиция - уменьшить
+        (self.output.len().min(1), None)
+    }
+}
@@ -0,0 +1,188 @@
+use alloc::vec::Vec;
+
+use crate::buffer::InlineBuffer;
+use crate::codepoint::Codepoint;
+use crate::sink::CompareOutput;
+use crate::slice::iter::CharsIter;
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
+use crate::ComposingForm;
+use crate::ComposingNormalizer;
+use crate::{MARKER_COMBINES_BACKWARDS, MARKER_NONSTARTER};
+
+/// результат быстрой проверки нормализации (UAX #15, Quick Check)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsNormalized
+{
+    /// строка нормализована
+    Yes,
+    /// строка не нормализована
+    No,
+    /// без нормализации ответить нельзя
+    Maybe,
+}
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// быстрая проверка нормализации строки без аллокаций.
+    /// кодпоинты, которые могут скомбинироваться с предыдущими (в том числе нестартеры), дают ответ
+    /// "возможно"
+    pub fn quick_check(&self, input: &str) -> IsNormalized
+    {
+        let first_code_boundary = self.first_code_boundary();
+        let iter = &mut CharsIter::new(input);
+
+        let mut result = IsNormalized::Yes;
+        let mut last_ccc = 0;
+
+        while !iter.is_empty() {
+            let first = unsafe { iter.next_unchecked() };
+
+            if first < first_code_boundary {
+                iter.skip_below(first_code_boundary);
+                last_ccc = 0;
+                continue;
+            }
+
+            let code = unsafe { iter.next_nonascii_bytes_unchecked(first) };
+            let dec_value = self.get_decomposition_value(code);
+
+            if dec_value & 1 == 0 {
+                last_ccc = 0;
+                continue;
+            }
+
+            match (dec_value as u8) >> 1 {
+                MARKER_NONSTARTER => {
+                    let ccc = (dec_value >> 8) as u8;
+
+                    // нарушен канонический порядок
+                    if ccc < last_ccc {
+                        return IsNormalized::No;
+                    }
+
+                    last_ccc = ccc;
+
+                    // NF(K)C_QC = Maybe, остальные нестартеры проходят проверку
+                    if dec_value & (1 << 16) != 0 {
+                        result = IsNormalized::Maybe;
+                    }
+                }
+                MARKER_COMBINES_BACKWARDS => {
+                    last_ccc = 0;
+                    result = IsNormalized::Maybe;
+                }
+                // синглтоны, декомпозиции, исключения композиции
+                _ => return IsNormalized::No,
+            }
+        }
+
+        result
+    }
+
+    /// является ли строка нормализованной?
+    pub fn is_normalized(&self, input: &str) -> bool
+    {
+        self.is_normalized_up_to(input) == input.len()
+    }
+
+    /// длина (в байтах) наибольшего начала строки, которое уже нормализовано и не изменится при нормализации
+    /// всей строки. нормализуются только отрезки, не прошедшие быструю проверку, результат сравнивается
+    /// с отрезком без записи. память выделяется только для последовательностей комбинируемых кодпоинтов
+    /// длиннее INLINE_BUFFER_CAPACITY
+    pub fn is_normalized_up_to(&self, input: &str) -> usize
+    {
+        let first_code_boundary = self.first_code_boundary();
+        let iter = &mut CharsIter::new(input);
+
+        // начало предыдущего кодпоинта, прошедшего быструю проверку
+        let mut previous = 0;
+
+        loop {
+            if iter.is_empty() {
+                return input.len();
+            }
+
+            let position = input.len() - iter.remaining();
+            let first = unsafe { iter.next_unchecked() };
+
+            if first < first_code_boundary {
+                // байты продолжения UTF-8 последовательности пропускаем
+                if first & 0xC0 != 0x80 {
+                    previous = position;
+                }
+
+                continue;
+            }
+
+            let code = unsafe { iter.next_nonascii_bytes_unchecked(first) };
+
+            if self.get_decomposition_value(code) & 1 == 0 {
+                previous = position;
+                continue;
+            }
+
+            // отрезок начинается с предыдущего стартера (с ним может скомбинироваться текущий кодпоинт)
+            // и заканчивается перед следующим кодпоинтом, прошедшим быструю проверку
+            let start = previous;
+            let end = self.find_passing_codepoint(iter, input.len(), first_code_boundary);
+
+            if !self.is_segment_normalized(&input[start .. end]) {
+                return start;
+            }
+
+            previous = end;
+        }
+    }
+
+    /// нормализация отрезка не меняет его?
+    fn is_segment_normalized(&self, segment: &str) -> bool
+    {
+        let mut output = CompareOutput::new(segment);
+        let mut buffer = InlineBuffer::new();
+
+        self.normalize_into(segment, &mut output, &mut buffer);
+
+        if !buffer.is_overflowed() {
+            return output.is_unchanged();
+        }
+
+        let mut output = CompareOutput::new(segment);
+        self.normalize_into(segment, &mut output, &mut Vec::<Codepoint>::new());
+
+        output.is_unchanged()
+    }
+
+    /// найти следующий кодпоинт, прошедший быструю проверку, вернуть его позицию (или длину строки)
+    #[inline(always)]
+    fn find_passing_codepoint(&self, iter: &mut CharsIter, length: usize, boundary: u8) -> usize
+    {
+        while !iter.is_empty() {
+            let position = length - iter.remaining();
+            let first = unsafe { iter.next_unchecked() };
+
+            if first < boundary {
+                return position;
+            }
+
+            let code = unsafe { iter.next_nonascii_bytes_unchecked(first) };
+
+            if self.get_decomposition_value(code) & 1 == 0 {
+                return position;
+            }
+        }
+
+        length
+    }
+
+    /// первый байт UTF-8 последовательности, начиная с которого кодпоинты требуют проверки
+    #[inline(always)]
+    fn first_code_boundary(&self) -> u8
+    {
+        match self.form {
+            #[cfg(feature = "nfc")]
+            ComposingForm::Nfc => 0xCC,
+            #[cfg(feature = "nfkc")]
+            ComposingForm::Nfkc => 0xC2,
+        }
+    }
+}
@@ -0,0 +1,456 @@
+use alloc::borrow::{Cow, ToOwned};
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+
+/// приёмник результата нормализации
+pub trait NormalizeSink
+{
+    /// ошибка записи
+    type Error;
+
+    /// дописать строку
+    fn write_str(&mut self, string: &str) -> Result<(), Self::Error>;
+
+    /// дописать символ
+    #[inline]
+    fn write_char(&mut self, c: char) -> Result<(), Self::Error>
+    {
+        self.write_str(c.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl NormalizeSink for String
+{
+    type Error = Infallible;
+
+    #[inline]
+    fn write_str(&mut self, string: &str) -> Result<(), Self::Error>
+    {
+        self.push_str(string);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> Result<(), Self::Error>
+    {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl<S: NormalizeSink + ?Sized> NormalizeSink for &mut S
+{
+    type Error = S::Error;
+
+    #[inline]
+    fn write_str(&mut self, string: &str) -> Result<(), Self::Error>
+    {
+        (**self).write_str(string)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> Result<(), Self::Error>
+    {
+        (**self).write_char(c)
+    }
+}
+
+/// приёмник, реализующий core::fmt::Write
+pub struct FmtSink<W: core::fmt::Write>(pub W);
+
+impl<W: core::fmt::Write> NormalizeSink for FmtSink<W>
+{
+    type Error = core::fmt::Error;
+
+    #[inline]
+    fn write_str(&mut self, string: &str) -> Result<(), Self::Error>
+    {
+        self.0.write_str(string)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> Result<(), Self::Error>
+    {
+        self.0.write_char(c)
+    }
+}
+
+/// приёмник, реализующий std::io::Write, результат пишется в UTF-8
+#[cfg(feature = "std")]
+pub struct IoSink<W: std::io::Write>(pub W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> NormalizeSink for IoSink<W>
+{
+    type Error = std::io::Error;
+
+    #[inline]
+    fn write_str(&mut self, string: &str) -> Result<(), Self::Error>
+    {
+        self.0.write_all(string.as_bytes())
+    }
+}
+
+/// результат нормализации: последний записанный символ может быть забран обратно
+/// для комбинирования с последующими кодпоинтами
+pub trait Output
+{
+    /// дописать строку
+    fn push_str(&mut self, string: &str);
+
+    /// дописать символ
+    fn push(&mut self, c: char);
+
+    /// забрать последний записанный символ
+    fn pop(&mut self) -> Option<char>;
+
+    /// запись прекращена (ошибка приёмника) - нормализацию оставшейся части строки можно прервать
+    #[inline(always)]
+    fn is_stopped(&self) -> bool
+    {
+        false
+    }
+}
+
+impl Output for String
+{
+    #[inline(always)]
+    fn push_str(&mut self, string: &str)
+    {
+        String::push_str(self, string)
+    }
+
+    #[inline(always)]
+    fn push(&mut self, c: char)
+    {
+        String::push(self, c)
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<char>
+    {
+        String::pop(self)
+    }
+}
+
+/// результат нормализации, сравниваемый с исходной строкой: пока результат совпадает с ней,
+/// он не записывается - запоминается длина совпавшего начала
+pub struct CompareOutput<'s>
+{
+    input: &'s str,
+    /// длина совпавшего с исходной строкой начала результата
+    matched: usize,
+    /// после расхождения результат записывается (совпавшее начало копируется один раз)
+    copies: bool,
+    /// результат после расхождения
+    divergence: Divergence,
+}
+
+/// результат после расхождения с исходной строкой
+enum Divergence
+{
+    /// расхождения не было
+    None,
+    /// результат не записывается, забрать можно лишь последний записанный символ
+    Discarded(char),
+    /// совпавшее начало и результат после расхождения
+    Copied(String),
+}
+
+impl<'s> CompareOutput<'s>
+{
+    /// сравнение без записи результата
+    #[inline(always)]
+    pub fn new(input: &'s str) -> Self
+    {
+        Self {
+            input,
+            matched: 0,
+            copies: false,
+            divergence: Divergence::None,
+        }
+    }
+
+    /// сравнение с записью результата после первого расхождения
+    #[inline(always)]
+    pub fn copying(input: &'s str) -> Self
+    {
+        Self {
+            copies: true,
+            ..Self::new(input)
+        }
+    }
+
+    /// результат совпал с исходной строкой?
+    #[inline(always)]
+    pub fn is_unchanged(&self) -> bool
+    {
+        matches!(self.divergence, Divergence::None) && self.matched == self.input.len()
+    }
+
+    /// результат: исходная строка, если он с ней совпал, иначе - записанный результат
+    /// (для сравнения с записью результата)
+    pub fn into_cow(self) -> Cow<'s, str>
+    {
+        match self.divergence {
+            Divergence::Copied(result) => Cow::Owned(result),
+            _ if self.matched == self.input.len() => Cow::Borrowed(self.input),
+            _ => Cow::Owned(self.input[.. self.matched].to_owned()),
+        }
+    }
+
+    /// первое расхождение с исходной строкой
+    #[cold]
+    fn diverge(&mut self, string: &str)
+    {
+        self.divergence = match self.copies {
+            true => {
+                let mut result = String::with_capacity(self.input.len() + string.len());
+
+                result.push_str(&self.input[.. self.matched]);
+                result.push_str(string);
+
+                Divergence::Copied(result)
+            }
+            false => Divergence::Discarded(string.chars().next_back().unwrap_or('\0')),
+        }
+    }
+}
+
+impl<'s> Output for CompareOutput<'s>
+{
+    #[inline(always)]
+    fn push_str(&mut self, string: &str)
+    {
+        match &mut self.divergence {
+            Divergence::None => (),
+            Divergence::Discarded(last) => {
+                if let Some(c) = string.chars().next_back() {
+                    *last = c;
+                }
+
+                return;
+            }
+            Divergence::Copied(result) => return result.push_str(string),
+        }
+
+        let rest = &self.input.as_bytes()[self.matched ..];
+
+        // отрезки, пропущенные быстрыми циклами, - части самой исходной строки
+        let is_same = string.len() <= rest.len()
+            && (string.as_ptr() == rest.as_ptr() || rest.starts_with(string.as_bytes()));
+
+        match is_same {
+            true => self.matched += string.len(),
+            false => self.diverge(string),
+        }
+    }
+
+    #[inline(always)]
+    fn push(&mut self, c: char)
+    {
+        self.push_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<char>
+    {
+        match &mut self.divergence {
+            Divergence::None => {
+                // совпавшее начало результата - начало исходной строки
+                let c = self.input[.. self.matched].chars().next_back()?;
+                self.matched -= c.len_utf8();
+
+                Some(c)
+            }
+            Divergence::Discarded(last) => Some(*last),
+            Divergence::Copied(result) => result.pop(),
+        }
+    }
+}
+
+impl Output for VecDeque<char>
+{
+    #[inline(always)]
+    fn push_str(&mut self, string: &str)
+    {
+        self.extend(string.chars())
+    }
+
+    #[inline(always)]
+    fn push(&mut self, c: char)
+    {
+        self.push_back(c)
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<char>
+    {
+        self.pop_back()
+    }
+}
+
+impl Output for Vec<char>
+{
+    #[inline(always)]
+    fn push_str(&mut self, string: &str)
+    {
+        self.extend(string.chars())
+    }
+
+    #[inline(always)]
+    fn push(&mut self, c: char)
+    {
+        Vec::push(self, c)
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<char>
+    {
+        Vec::pop(self)
+    }
+}
+
+/// UTF-16. непарный суррогат (записывается при сохранении непарных суррогатов) забрать нельзя:
+/// для него возвращается None, и он остаётся в результате
+impl Output for Vec<u16>
+{
+    #[inline(always)]
+    fn push_str(&mut self, string: &str)
+    {
+        self.extend(string.encode_utf16())
+    }
+
+    #[inline(always)]
+    fn push(&mut self, c: char)
+    {
+        let mut units = [0; 2];
+        self.extend_from_slice(c.encode_utf16(&mut units))
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<char>
+    {
+        let length = self.len();
+
+        match *self.last()? {
+            low @ 0xDC00 ..= 0xDFFF => {
+                let high = match length > 1 {
+                    true => self[length - 2],
+                    false => return None,
+                };
+
+                if !(0xD800 ..= 0xDBFF).contains(&high) {
+                    return None;
+                }
+
+                self.truncate(length - 2);
+
+                let code = 0x10000 + ((u32::from(high) - 0xD800) << 10) + (u32::from(low) - 0xDC00);
+                char::from_u32(code)
+            }
+            0xD800 ..= 0xDBFF => None,
+            unit => {
+                self.truncate(length - 1);
+                char::from_u32(u32::from(unit))
+            }
+        }
+    }
+}
+
+/// запись результата в приёмник. нормализатор забирает обратно не более одного символа подряд,
+/// поэтому последний символ придерживается до следующей записи
+pub struct SinkOutput<'s, S: NormalizeSink>
+{
+    sink: &'s mut S,
+    /// последний записанный символ, ещё не переданный в приёмник
+    last: Option<char>,
+    /// первая ошибка записи, после неё запись и нормализация прекращаются
+    error: Option<S::Error>,
+}
+
+impl<'s, S: NormalizeSink> SinkOutput<'s, S>
+{
+    #[inline]
+    pub fn new(sink: &'s mut S) -> Self
+    {
+        Self {
+            sink,
+            last: None,
+            error: None,
+        }
+    }
+
+    /// передать в приёмник придержанный символ, вернуть ошибку записи, если она была
+    #[inline]
+    pub fn finish(mut self) -> Result<(), S::Error>
+    {
+        if let Some(c) = self.last.take() {
+            self.write_char(c);
+        }
+
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    #[inline(always)]
+    fn write_char(&mut self, c: char)
+    {
+        if self.error.is_none() {
+            if let Err(error) = self.sink.write_char(c) {
+                self.error = Some(error);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn write_str(&mut self, string: &str)
+    {
+        if self.error.is_none() && !string.is_empty() {
+            if let Err(error) = self.sink.write_str(string) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+impl<'s, S: NormalizeSink> Output for SinkOutput<'s, S>
+{
+    #[inline(always)]
+    fn push_str(&mut self, string: &str)
+    {
+        let mut chars = string.chars();
+
+        if let Some(last) = chars.next_back() {
+            if let Some(c) = self.last.replace(last) {
+                self.write_char(c);
+            }
+
+            self.write_str(chars.as_str());
+        }
+    }
+
+    #[inline(always)]
+    fn push(&mut self, c: char)
+    {
+        if let Some(previous) = self.last.replace(c) {
+            self.write_char(previous);
+        }
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<char>
+    {
+        self.last.take()
+    }
+
+    #[inline(always)]
+    fn is_stopped(&self) -> bool
+    {
+        self.error.is_some()
+    }
+}
@@ -1,6 +1,10 @@
 use core::marker::PhantomData;
 use core::slice::from_raw_parts;
 
+use super::scan::{skip_below, skip_passing};
+use crate::passes_quick_check;
+
+#[derive(Clone)]
 #[repr(align(16))]
 pub struct CharsIter<'a>
 {
@@ -50,6 +54,114 @@ impl<'a> CharsIter<'a>
         unsafe { self.end.offset_from(self.ptr) == 0 }
     }
 
+    /// количество оставшихся байт
+    #[inline(always)]
+    pub fn remaining(&self) -> usize
+    {
+        unsafe { self.end.offset_from(self.ptr) as usize }
+    }
+
+    /// пропустить байты, меньшие границы. одиночные байты (например, пробелы между словами
+    /// нелатинского текста) пропускаются без обращения к векторной проверке
+    #[inline(always)]
+    pub fn skip_below(&mut self, boundary: u8)
+    {
+        if self.is_empty() || unsafe { *self.ptr } >= boundary {
+            return;
+        }
+
+        self.ptr = unsafe { skip_below(self.ptr, self.end, boundary) };
+    }
+
+    /// пропустить байты, меньшие границы, и 2- и 3-байтовые последовательности, прошедшие быструю
+    /// проверку. перед последовательностью, не прошедшей её (например, после пробела в тексте
+    /// на хинди), пропуск не начинается
+    #[inline(always)]
+    pub fn skip_passing(&mut self, boundary: u8, blocks: &[u64; 64])
+    {
+        if self.is_empty() {
+            return;
+        }
+
+        let first = unsafe { *self.ptr };
+
+        if first >= 0xE0 && !passes_quick_check(blocks, first, unsafe { *self.ptr.add(1) }) {
+            return;
+        }
+
+        self.ptr = unsafe { skip_passing(self.ptr, self.end, boundary, blocks) };
+    }
+
+    /// следующий байт без проверки длины оставшихся данных, указатель не сдвигается
+    #[inline(always)]
+    pub unsafe fn peek_unchecked(&self) -> u8
+    {
+        unsafe { *self.ptr }
+    }
+
+    /// два следующих байта без проверки длины оставшихся данных, указатель не сдвигается
+    #[inline(always)]
+    pub unsafe fn peek_two_unchecked(&self) -> (u8, u8)
+    {
+        unsafe { (*self.ptr, *self.ptr.add(1)) }
+    }
+
+    /// пропустить байты без проверки длины оставшихся данных
+    #[inline(always)]
+    pub unsafe fn skip_unchecked(&mut self, count: usize)
+    {
+        self.ptr = unsafe { self.ptr.add(count) };
+    }
+
+    /// количество байтов ASCII, следующих за указателем
+    #[cfg(any(feature = "nfc", feature = "nfkc"))]
+    #[inline(always)]
+    pub fn peek_ascii(&self) -> usize
+    {
+        let bytes = unsafe { from_raw_parts(self.ptr, self.remaining()) };
+
+        bytes
+            .iter()
+            .position(|&byte| byte >= 0x80)
+            .unwrap_or(bytes.len())
+    }
+
+    /// прочитать count байт без проверки длины оставшихся данных
+    #[cfg(any(feature = "nfc", feature = "nfkc"))]
+    #[inline(always)]
+    pub unsafe fn next_slice_unchecked(&mut self, count: usize) -> &'a [u8]
+    {
+        let slice = unsafe { from_raw_parts(self.ptr, count) };
+        self.ptr = unsafe { self.ptr.add(count) };
+
+        slice
+    }
+
+    /// кодпоинт 3-байтовой последовательности, начинающейся через offset байт после указателя
+    #[cfg(any(feature = "nfc", feature = "nfkc"))]
+    #[inline(always)]
+    pub fn peek_three_bytes(&self, offset: usize) -> Option<u32>
+    {
+        three_bytes_at(
+            unsafe { from_raw_parts(self.ptr, self.remaining()) },
+            offset,
+        )
+    }
+
+    /// кодпоинт 3-байтовой последовательности, заканчивающейся за offset байт до указателя, если
+    /// она находится после запомненной позиции
+    #[cfg(any(feature = "nfc", feature = "nfkc"))]
+    #[inline(always)]
+    pub fn block_last_three_bytes(&self, offset: usize) -> Option<u32>
+    {
+        let length = unsafe { self.ptr.offset_from(self.breakpoint) } as usize;
+
+        match length >= offset + 3 {
+            true => three_bytes_at(self.block_slice(0), length - offset - 3),
+            false => None,
+        }
+    }
+
     /// прочитать байт без проверки длины оставшихся данных
     #[inline(always)]
     pub unsafe fn next_unchecked(&mut self) -> u8
@@ -107,6 +219,20 @@ impl<'a> CharsIter<'a>
     }
 }
 
+/// кодпоинт 3-байтовой последовательности UTF-8, если с заданной позиции начинается она (байты
+/// продолжения и первые байты других последовательностей отличаются старшими битами)
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
+#[inline(always)]
+fn three_bytes_at(bytes: &[u8], position: usize) -> Option<u32>
+{
+    match bytes.get(position .. position + 3) {
+        Some(&[x, y, z]) if x & 0xF0 == 0xE0 => {
+            Some(((x & 0x0F) as u32) << 12 | ((y & CONT_MASK) as u32) << 6 | (z & CONT_MASK) as u32)
+        }
+        _ => None,
+    }
+}
+
 /// маска, использующаяся для получения битов значения первого байта UTF-8
 const FIRST_BYTE_VALUE_MASK: u8 = 0x7F;
 /// маска, исключащая 2 старших бита в 2, 3, 4 байтах последовательности UTF-8
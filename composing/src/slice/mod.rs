@@ -1,2 +1,2 @@
-pub mod aligned;
 pub mod iter;
+pub mod scan;
@@ -0,0 +1,231 @@
+// поиск первого байта, не меньшего границы быстрой проверки. байты меньше границы - ASCII, байты
+// продолжения последовательностей UTF-8 и первые байты кодпоинтов, заведомо прошедших быструю
+// проверку, - их можно пропускать целыми блоками, не декодируя. 2- и 3-байтовые последовательности
+// проверяются по первым двум байтам (битовая карта кодпоинтов и блоков из 64 кодпоинтов
+// нормализатора), также без декодирования.
+//
+// на x86_64 используется AVX2 (если доступен - определяется во время выполнения при наличии std,
+// или на этапе компиляции) или SSE2, на остальных платформах - проверка машинными словами
+
+use core::slice::from_raw_parts;
+
+use crate::passes_quick_check;
+
+/// указатель на первый байт в промежутке ptr .. end, не меньший границы, или end.
+/// не встраивается в цикл быстрой проверки, чтобы не увеличивать нагрузку на регистры
+#[inline(never)]
+pub unsafe fn skip_below(ptr: *const u8, end: *const u8, boundary: u8) -> *const u8
+{
+    let bytes = unsafe { from_raw_parts(ptr, end.offset_from(ptr) as usize) };
+
+    unsafe { ptr.add(skip_below_slice(bytes, boundary)) }
+}
+
+/// указатель на первый байт в промежутке ptr .. end, не меньший границы и не являющийся началом
+/// 2- или 3-байтовой последовательности, прошедшей быструю проверку, или end
+#[inline(never)]
+pub unsafe fn skip_passing(
+    mut ptr: *const u8,
+    end: *const u8,
+    boundary: u8,
+    blocks: &[u64; 64],
+) -> *const u8
+{
+    // блоками: проверяем только первые байты последовательностей, не меньшие границы - байты
+    // продолжения и ASCII меньше неё. второй байт последовательности, начинающейся в конце блока,
+    // есть всегда - строка валидна
+    #[cfg(target_arch = "x86_64")]
+    while unsafe { end.offset_from(ptr) } >= x86::SSE2_BLOCK as isize {
+        let mut mask = unsafe { x86::sse2_mask(ptr, boundary) };
+
+        while mask != 0 {
+            let position = mask.trailing_zeros() as usize;
+            let first = unsafe { *ptr.add(position) };
+            let second = unsafe { *ptr.add(position + 1) };
+
+            if !passes_quick_check(blocks, first, second) {
+                return unsafe { ptr.add(position) };
+            }
+
+            mask &= mask - 1;
+        }
+
+        ptr = unsafe { ptr.add(x86::SSE2_BLOCK) };
+    }
+
+    // остаток побайтово, байты продолжения пропускаются как меньшие границы
+    while ptr < end {
+        let first = unsafe { *ptr };
+
+        if first >= boundary && !passes_quick_check(blocks, first, unsafe { *ptr.add(1) }) {
+            break;
+        }
+
+        ptr = unsafe { ptr.add(1) };
+    }
+
+    ptr
+}
+
+/// количество байт с начала слайса, меньших границы
+#[inline(always)]
+fn skip_below_slice(bytes: &[u8], boundary: u8) -> usize
+{
+    #[cfg(target_arch = "x86_64")]
+    {
+        if bytes.len() >= x86::SSE2_BLOCK {
+            return unsafe { x86::skip_below(bytes, boundary) };
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        if bytes.len() >= WORD {
+            return skip_below_words(bytes, boundary);
+        }
+    }
+
+    skip_below_bytes(bytes, 0, boundary)
+}
+
+/// размер машинного слова
+#[cfg(not(target_arch = "x86_64"))]
+const WORD: usize = core::mem::size_of::<usize>();
+
+/// побайтовая проверка, начиная с заданной позиции
+#[inline(always)]
+fn skip_below_bytes(bytes: &[u8], from: usize, boundary: u8) -> usize
+{
+    bytes[from ..]
+        .iter()
+        .position(|&byte| byte >= boundary)
+        .map_or(bytes.len(), |position| from + position)
+}
+
+/// проверка машинными словами: граница всегда не меньше 0x80, поэтому слово, в котором нет
+/// байтов со старшим битом, пропускается целиком
+#[cfg(not(target_arch = "x86_64"))]
+#[inline(always)]
+fn skip_below_words(bytes: &[u8], boundary: u8) -> usize
+{
+    const HIGH_BITS: usize = usize::from_ne_bytes([0x80; WORD]);
+
+    let mut position = 0;
+
+    while position + WORD <= bytes.len() {
+        let word = usize::from_ne_bytes(bytes[position .. position + WORD].try_into().unwrap());
+
+        if word & HIGH_BITS != 0 {
+            // в слове есть байт не из ASCII - проверяем его побайтово
+            if let Some(offset) = bytes[position .. position + WORD]
+                .iter()
+                .position(|&byte| byte >= boundary)
+            {
+                return position + offset;
+            }
+        }
+
+        position += WORD;
+    }
+
+    skip_below_bytes(bytes, position, boundary)
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86
+{
+    use core::arch::x86_64::*;
+
+    /// размер блока SSE2
+    pub const SSE2_BLOCK: usize = 16;
+    /// размер блока AVX2
+    #[cfg(any(target_feature = "avx2", feature = "std"))]
+    const AVX2_BLOCK: usize = 32;
+
+    /// выбор реализации. слайс не короче SSE2_BLOCK
+    #[inline(always)]
+    pub unsafe fn skip_below(bytes: &[u8], boundary: u8) -> usize
+    {
+        // короткие участки (пробелы и знаки препинания между словами) проверяются первым блоком,
+        // без выбора реализации и вызова функции
+        let mask = unsafe { sse2_mask(bytes.as_ptr(), boundary) };
+
+        if mask != 0 {
+            return mask.trailing_zeros() as usize;
+        }
+
+        #[cfg(target_feature = "avx2")]
+        return unsafe { skip_below_avx2(bytes, SSE2_BLOCK, boundary) };
+
+        #[cfg(all(not(target_feature = "avx2"), feature = "std"))]
+        if bytes.len() >= SSE2_BLOCK + AVX2_BLOCK && std::is_x86_feature_detected!("avx2") {
+            return unsafe { skip_below_avx2(bytes, SSE2_BLOCK, boundary) };
+        }
+
+        #[cfg(not(target_feature = "avx2"))]
+        unsafe {
+            skip_below_sse2(bytes, SSE2_BLOCK, boundary)
+        }
+    }
+
+    /// маска байтов блока SSE2, не меньших границы: max(байт, граница) == байт
+    #[inline(always)]
+    pub unsafe fn sse2_mask(ptr: *const u8, boundary: u8) -> u32
+    {
+        let vboundary = _mm_set1_epi8(boundary as i8);
+        let block = unsafe { _mm_loadu_si128(ptr as *const __m128i) };
+
+        _mm_movemask_epi8(_mm_cmpeq_epi8(_mm_max_epu8(block, vboundary), block)) as u32
+    }
+
+    /// SSE2 - блоками по 16 байт, начиная с заданной позиции
+    #[inline]
+    unsafe fn skip_below_sse2(bytes: &[u8], mut position: usize, boundary: u8) -> usize
+    {
+        while position + SSE2_BLOCK <= bytes.len() {
+            let mask = unsafe { sse2_mask(bytes.as_ptr().add(position), boundary) };
+
+            if mask != 0 {
+                return position + mask.trailing_zeros() as usize;
+            }
+
+            position += SSE2_BLOCK;
+        }
+
+        // остаток - последним блоком, перекрывающимся с уже проверенными байтами
+        if position < bytes.len() {
+            position = bytes.len() - SSE2_BLOCK;
+
+            let mask = unsafe { sse2_mask(bytes.as_ptr().add(position), boundary) };
+
+            if mask != 0 {
+                return position + mask.trailing_zeros() as usize;
+            }
+        }
+
+        bytes.len()
+    }
+
+    /// AVX2 - блоками по 32 байта, начиная с заданной позиции, остаток - SSE2
+    #[cfg(any(target_feature = "avx2", feature = "std"))]
+    #[target_feature(enable = "avx2")]
+    unsafe fn skip_below_avx2(bytes: &[u8], mut position: usize, boundary: u8) -> usize
+    {
+        let ptr = bytes.as_ptr();
+        let vboundary = _mm256_set1_epi8(boundary as i8);
+
+        while position + AVX2_BLOCK <= bytes.len() {
+            let block = unsafe { _mm256_loadu_si256(ptr.add(position) as *const __m256i) };
+            let mask =
+                _mm256_movemask_epi8(_mm256_cmpeq_epi8(_mm256_max_epu8(block, vboundary), block));
+
+            if mask != 0 {
+                return position + mask.trailing_zeros() as usize;
+            }
+
+            position += AVX2_BLOCK;
+        }
+
+        unsafe { skip_below_sse2(bytes, position, boundary) }
+    }
+}
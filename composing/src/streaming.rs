@@ -0,0 +1,180 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::buffer::Buffer;
+use crate::codepoint::Codepoint;
+use crate::composition::{combine_and_write, Combining};
+use crate::sink::Output;
+#[cfg(any(feature = "nfc", feature = "nfkc"))]
+use crate::ComposingForm;
+use crate::ComposingNormalizer;
+
+/// потоковая нормализация текста, поступающего произвольными частями.
+///
+/// между частями сохраняется состояние нормализатора: буфер комбинируемых кодпоинтов вместе с
+/// информацией о комбинировании и последний записанный кодпоинт, если его ещё может изменить начало
+/// следующей части. остальной результат окончателен и возвращается сразу
+pub struct StreamingNormalizer<'n, 'a>
+{
+    normalizer: &'n ComposingNormalizer<'a>,
+    /// буфер комбинируемых кодпоинтов окончания полученного текста
+    buffer: Vec<Codepoint>,
+    /// информация о комбинировании стартера в буфере
+    combining: Combining,
+    /// последний записанный кодпоинт, который может скомбинироваться с началом следующей части
+    held: Option<char>,
+    /// результат последнего вызова push / finish
+    output: String,
+}
+
+impl<'n, 'a> StreamingNormalizer<'n, 'a>
+{
+    /// потоковый нормализатор
+    pub fn new(normalizer: &'n ComposingNormalizer<'a>) -> Self
+    {
+        Self {
+            normalizer,
+            buffer: Vec::with_capacity(18),
+            combining: Combining::None,
+            held: None,
+            output: String::new(),
+        }
+    }
+
+    /// нормализовать очередную часть текста, вернуть окончательный результат
+    pub fn push(&mut self, chunk: &str) -> &str
+    {
+        let mut output = core::mem::take(&mut self.output);
+
+        output.clear();
+        self.push_into(chunk, &mut output);
+
+        self.output = output;
+        &self.output
+    }
+
+    /// нормализовать очередную часть текста, дописать окончательный результат в строку
+    pub fn push_into(&mut self, chunk: &str, result: &mut String)
+    {
+        let start = result.len();
+        let mut output = ChunkOutput { result, start };
+
+        if let Some(held) = self.held.take() {
+            output.push(held);
+        }
+
+        let passed = self.normalizer.normalize_chunk(
+            chunk,
+            &mut output,
+            &mut self.buffer,
+            &mut self.combining,
+        );
+
+        // буфер не пуст - записанный результат уже не изменится
+        if !self.buffer.is_empty() {
+            return;
+        }
+
+        if let Some(previous) = output.pop() {
+            match passed {
+                // кодпоинт из быстрого цикла: следующий нестартер комбинируется с ним через буфер
+                true => {
+                    self.combining = self.normalizer.buffer_previous(
+                        &mut output,
+                        &mut self.buffer,
+                        u32::from(previous),
+                    )
+                }
+                // кодпоинт может забрать только комбинирование с предыдущим (хангыль и т.п.)
+                false => self.held = Some(previous),
+            }
+        }
+    }
+
+    /// завершить поток: нормализовать придержанное окончание и вернуть его.
+    /// после этого нормализатор можно использовать для нового потока
+    pub fn finish(&mut self) -> &str
+    {
+        let mut output = core::mem::take(&mut self.output);
+
+        output.clear();
+        self.finish_into(&mut output);
+
+        self.output = output;
+        &self.output
+    }
+
+    /// завершить поток, дописав нормализованное придержанное окончание в строку
+    pub fn finish_into(&mut self, result: &mut String)
+    {
+        if let Some(held) = self.held.take() {
+            result.push(held);
+        }
+
+        combine_and_write(
+            result,
+            &mut self.buffer,
+            self.combining,
+            &self.normalizer.compositions,
+        );
+
+        self.combining = Combining::None;
+    }
+}
+
+/// результат нормализации части потока: забрать можно только записанное при обработке этой части
+struct ChunkOutput<'r>
+{
+    result: &'r mut String,
+    start: usize,
+}
+
+impl<'r> Output for ChunkOutput<'r>
+{
+    #[inline(always)]
+    fn push_str(&mut self, string: &str)
+    {
+        self.result.push_str(string)
+    }
+
+    #[inline(always)]
+    fn push(&mut self, c: char)
+    {
+        self.result.push(c)
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<char>
+    {
+        match self.result.len() > self.start {
+            true => self.result.pop(),
+            false => None,
+        }
+    }
+}
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// нормализация части потока с сохранением буфера и комбинирования до следующей части.
+    /// возвращает, прошёл ли быструю проверку последний записанный кодпоинт
+    #[inline(never)]
+    #[cfg_attr(
+        not(any(feature = "nfc", feature = "nfkc")),
+        allow(unreachable_code, unused_variables)
+    )]
+    fn normalize_chunk(
+        &self,
+        input: &str,
+        result: &mut impl Output,
+        buffer: &mut impl Buffer,
+        combining: &mut Combining,
+    ) -> bool
+    {
+        match self.form() {
+            #[cfg(feature = "nfc")]
+            ComposingForm::Nfc => self.normalize_nfc::<true>(input, result, buffer, combining),
+            #[cfg(feature = "nfkc")]
+            ComposingForm::Nfkc => self.normalize_nfkc::<true>(input, result, buffer, combining),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::codepoint::Codepoint;
+use crate::composition::{combine_and_write, Combining};
+use crate::sink::Output;
+use crate::ComposingNormalizer;
+
+/// что делать с непарными суррогатами UTF-16
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnpairedSurrogates
+{
+    /// вернуть ошибку
+    Error,
+    /// заменить на U+FFFD
+    Replace,
+    /// оставить как есть (WTF-16). при нормализации в String заменяются на U+FFFD
+    Preserve,
+}
+
+/// ошибка нормализации UTF-16: непарный суррогат
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Utf16NormalizeError
+{
+    valid_up_to: usize,
+    unit: u16,
+}
+
+impl Utf16NormalizeError
+{
+    /// индекс (в 16-битных единицах) непарного суррогата
+    pub fn valid_up_to(&self) -> usize
+    {
+        self.valid_up_to
+    }
+
+    /// непарный суррогат
+    pub fn unit(&self) -> u16
+    {
+        self.unit
+    }
+}
+
+impl fmt::Display for Utf16NormalizeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(
+            f,
+            "unpaired surrogate {:#06X} at index {}",
+            self.unit, self.valid_up_to
+        )
+    }
+}
+
+impl core::error::Error for Utf16NormalizeError {}
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// нормализация UTF-16
+    pub fn normalize_utf16(
+        &self,
+        input: &[u16],
+        unpaired: UnpairedSurrogates,
+    ) -> Result<Vec<u16>, Utf16NormalizeError>
+    {
+        let mut result = Vec::with_capacity(input.len());
+
+        self.normalize_utf16_into(input, unpaired, &mut result)?;
+
+        Ok(result)
+    }
+
+    /// нормализация UTF-16 с результатом в UTF-8
+    pub fn normalize_utf16_to_string(
+        &self,
+        input: &[u16],
+        unpaired: UnpairedSurrogates,
+    ) -> Result<String, Utf16NormalizeError>
+    {
+        let mut result = String::with_capacity(input.len());
+
+        self.normalize_utf16_into(input, unpaired, &mut result)?;
+
+        Ok(result)
+    }
+
+    /// декодирование UTF-16 и нормализация по кодпоинтам. кодпоинты до U+0300 (NFC) и U+00A0 (NFKC)
+    /// не требуют обращения к данным
+    fn normalize_utf16_into(
+        &self,
+        input: &[u16],
+        unpaired: UnpairedSurrogates,
+        result: &mut impl Utf16Output,
+    ) -> Result<(), Utf16NormalizeError>
+    {
+        let mut buffer: Vec<Codepoint> = Vec::with_capacity(18);
+        let mut combining = Combining::None;
+        let mut passed = false;
+
+        let mut position = 0;
+
+        while position < input.len() {
+            let unit = input[position];
+            position += 1;
+
+            let code = match unit {
+                0xD800 ..= 0xDBFF
+                    if position < input.len() && (0xDC00 ..= 0xDFFF).contains(&input[position]) =>
+                {
+                    let low = input[position];
+                    position += 1;
+
+                    0x10000 + ((u32::from(unit) - 0xD800) << 10) + (u32::from(low) - 0xDC00)
+                }
+                0xD800 ..= 0xDFFF => match unpaired {
+                    UnpairedSurrogates::Error => {
+                        return Err(Utf16NormalizeError {
+                            valid_up_to: position - 1,
+                            unit,
+                        })
+                    }
+                    UnpairedSurrogates::Replace => 0xFFFD,
+                    UnpairedSurrogates::Preserve => {
+                        // непарный суррогат ни с чем не комбинируется - завершаем последовательность
+                        combine_and_write(result, &mut buffer, combining, &self.compositions);
+                        result.push_unpaired_surrogate(unit);
+
+                        combining = Combining::None;
+                        passed = false;
+                        continue;
+                    }
+                },
+                _ => u32::from(unit),
+            };
+
+            self.normalize_codepoint(code, &mut combining, &mut passed, result, &mut buffer);
+        }
+
+        combine_and_write(result, &mut buffer, combining, &self.compositions);
+
+        Ok(())
+    }
+}
+
+/// результат нормализации UTF-16
+trait Utf16Output: Output
+{
+    /// дописать непарный суррогат
+    fn push_unpaired_surrogate(&mut self, unit: u16);
+}
+
+impl Utf16Output for Vec<u16>
+{
+    #[inline(always)]
+    fn push_unpaired_surrogate(&mut self, unit: u16)
+    {
+        self.push(unit)
+    }
+}
+
+impl Utf16Output for String
+{
+    #[inline(always)]
+    fn push_unpaired_surrogate(&mut self, _: u16)
+    {
+        self.push(char::REPLACEMENT_CHARACTER)
+    }
+}
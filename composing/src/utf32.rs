@@ -0,0 +1,100 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::codepoint::Codepoint;
+use crate::composition::{combine_and_write, Combining};
+use crate::ComposingNormalizer;
+
+/// ошибка нормализации последовательности кодпоинтов: значение не является скалярным значением Unicode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodepointNormalizeError
+{
+    valid_up_to: usize,
+    value: u32,
+}
+
+impl CodepointNormalizeError
+{
+    /// индекс невалидного значения
+    pub fn valid_up_to(&self) -> usize
+    {
+        self.valid_up_to
+    }
+
+    /// невалидное значение: суррогат или число больше U+10FFFF
+    pub fn value(&self) -> u32
+    {
+        self.value
+    }
+}
+
+impl fmt::Display for CodepointNormalizeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let kind = match self.value {
+            0xD800 ..= 0xDFFF => "surrogate",
+            _ => "out of range value",
+        };
+
+        write!(
+            f,
+            "{} {:#X} at index {}",
+            kind, self.value, self.valid_up_to
+        )
+    }
+}
+
+impl core::error::Error for CodepointNormalizeError {}
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// нормализация последовательности символов
+    pub fn normalize_chars(&self, input: &[char]) -> Vec<char>
+    {
+        let mut result = Vec::with_capacity(input.len());
+        let mut buffer = Vec::with_capacity(18);
+        let mut combining = Combining::None;
+        let mut passed = false;
+
+        for &c in input {
+            self.normalize_codepoint(
+                u32::from(c),
+                &mut combining,
+                &mut passed,
+                &mut result,
+                &mut buffer,
+            );
+        }
+
+        combine_and_write(&mut result, &mut buffer, combining, &self.compositions);
+
+        result
+    }
+
+    /// нормализация последовательности кодпоинтов (UTF-32). суррогаты и значения больше U+10FFFF -
+    /// ошибка с индексом первого невалидного значения
+    pub fn normalize_code_points(&self, input: &[u32])
+        -> Result<Vec<char>, CodepointNormalizeError>
+    {
+        let mut result = Vec::with_capacity(input.len());
+        let mut buffer: Vec<Codepoint> = Vec::with_capacity(18);
+        let mut combining = Combining::None;
+        let mut passed = false;
+
+        for (index, &code) in input.iter().enumerate() {
+            if char::from_u32(code).is_none() {
+                return Err(CodepointNormalizeError {
+                    valid_up_to: index,
+                    value: code,
+                });
+            }
+
+            self.normalize_codepoint(code, &mut combining, &mut passed, &mut result, &mut buffer);
+        }
+
+        combine_and_write(&mut result, &mut buffer, combining, &self.compositions);
+
+        Ok(result)
+    }
+}
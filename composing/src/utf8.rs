@@ -0,0 +1,122 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::codepoint::Codepoint;
+use crate::{ComposingNormalizer, StreamingNormalizer};
+
+/// размер блока, проверяемого перед нормализацией (помещается в кэш L1)
+const UTF8_BLOCK_SIZE: usize = 4096;
+
+/// ошибка нормализации байтов, не являющихся валидным UTF-8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Utf8NormalizeError
+{
+    valid_up_to: usize,
+    error_len: usize,
+}
+
+impl Utf8NormalizeError
+{
+    /// смещение (в байтах) первой невалидной последовательности
+    pub fn valid_up_to(&self) -> usize
+    {
+        self.valid_up_to
+    }
+
+    /// длина невалидной последовательности (наибольшей части, с которой может начинаться
+    /// валидная последовательность, или 1 байт)
+    pub fn error_len(&self) -> usize
+    {
+        self.error_len
+    }
+}
+
+impl fmt::Display for Utf8NormalizeError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(
+            f,
+            "invalid utf-8 sequence of {} bytes from index {}",
+            self.error_len, self.valid_up_to
+        )
+    }
+}
+
+impl core::error::Error for Utf8NormalizeError {}
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// нормализация байтов UTF-8. если встречается невалидная последовательность - возвращается ошибка
+    /// с её смещением.
+    ///
+    /// байты проверяются блоками непосредственно перед нормализацией, состояние нормализатора между
+    /// блоками сохраняется (как при потоковой нормализации). отдельного прохода проверки всей строки
+    /// нет, нормализация прекращается на первой ошибке
+    pub fn normalize_utf8(&self, input: &[u8]) -> Result<String, Utf8NormalizeError>
+    {
+        let mut result = String::with_capacity(input.len());
+        let mut stream = StreamingNormalizer::new(self);
+        let mut position = 0;
+
+        while position < input.len() {
+            let end = (position + UTF8_BLOCK_SIZE).min(input.len());
+
+            let (valid, error) = match core::str::from_utf8(&input[position .. end]) {
+                Ok(valid) => (valid, None),
+                Err(error) => {
+                    let valid = &input[position .. position + error.valid_up_to()];
+
+                    // SAFETY: начало до valid_up_to - валидный UTF-8
+                    (
+                        unsafe { core::str::from_utf8_unchecked(valid) },
+                        Some(error),
+                    )
+                }
+            };
+
+            stream.push_into(valid, &mut result);
+            position += valid.len();
+
+            let error_len = match error.map(|error| error.error_len()) {
+                None => continue,
+                Some(Some(error_len)) => error_len,
+                // последовательность разделена границей блока - проверяем её со следующим блоком
+                Some(None) if end < input.len() => continue,
+                // последовательность оборвана окончанием данных
+                Some(None) => input.len() - position,
+            };
+
+            return Err(Utf8NormalizeError {
+                valid_up_to: position,
+                error_len,
+            });
+        }
+
+        stream.finish_into(&mut result);
+
+        Ok(result)
+    }
+
+    /// нормализация байтов UTF-8 с заменой невалидных последовательностей на U+FFFD
+    /// (по правилу замены наибольших частей, как в WHATWG Encoding Standard).
+    ///
+    /// U+FFFD - стартер, который ни с чем не комбинируется, поэтому валидные части между заменами
+    /// нормализуются независимо
+    pub fn normalize_utf8_lossy(&self, input: &[u8]) -> String
+    {
+        let mut result = String::with_capacity(input.len());
+        let mut buffer: Vec<Codepoint> = Vec::with_capacity(18);
+
+        for chunk in input.utf8_chunks() {
+            self.normalize_into(chunk.valid(), &mut result, &mut buffer);
+
+            if !chunk.invalid().is_empty() {
+                result.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+
+        result
+    }
+}
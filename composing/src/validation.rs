@@ -0,0 +1,510 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::binary::DataError;
+use crate::composition::{composition_index, Combining};
+use crate::data::{CompositionData, DecompositionData, ExpansionsPatch, NormalizationData};
+use crate::{ComposingForm, ComposingNormalizer, DecomposingForm, DecomposingNormalizer};
+use crate::{
+    LAST_DECOMPOSING_CODEPOINT_BLOCK, MARKER_COMBINES_BACKWARDS, MARKER_EXPANSION,
+    MARKER_EXPANSION_COMBINED_EMPTY, MARKER_EXPANSION_COMBINED_PATCH, MARKER_HANGUL_SYLLABLE,
+    MARKER_NONSTARTER, MARKER_SINGLETON, MARKER_STARTER,
+};
+
+// нормализаторы доверяют данным: индексы таблиц не проверяются на этапе нормализации сверх обычных
+// проверок границ, а кодпоинты из таблиц пишутся в результат без проверки (char::from_u32_unchecked).
+// поэтому данные из непроверенного источника проверяются целиком перед созданием нормализатора
+
+/// последний кодпоинт Unicode
+const LAST_CODEPOINT: u32 = 0x10FFFF;
+/// слоги хангыль
+const HANGUL_SYLLABLES: core::ops::RangeInclusive<u32> = 0xAC00 ..= 0xD7A3;
+
+impl<'a> ComposingNormalizer<'a>
+{
+    /// заранее подготовленные данные с полной проверкой их целостности. данные не копируются.
+    /// если форма отключена возможностями крейта - DataError::FormDisabled
+    pub fn try_from_baked(
+        decomposition_data: DecompositionData<'a>,
+        compositions: CompositionData<'a>,
+        expansions_patch: ExpansionsPatch<'a>,
+        is_canonical: bool,
+    ) -> Result<Self, DataError>
+    {
+        ComposingForm::from_canonical(is_canonical).map(|_| ())?;
+
+        validate_composition(&decomposition_data, &compositions, &expansions_patch).map(|_| {
+            Self::from_baked(
+                decomposition_data,
+                compositions,
+                expansions_patch,
+                is_canonical,
+            )
+        })
+    }
+}
+
+impl<'a> DecomposingNormalizer<'a>
+{
+    /// заранее подготовленные данные с полной проверкой их целостности. данные не копируются.
+    /// если форма отключена возможностями крейта - DataError::FormDisabled
+    pub fn try_from_baked(
+        decomposition_data: DecompositionData<'a>,
+        is_canonical: bool,
+    ) -> Result<Self, DataError>
+    {
+        DecomposingForm::from_canonical(is_canonical).map(|_| ())?;
+
+        validate_decomposition(&decomposition_data)
+            .map(|_| Self::from_baked(decomposition_data, is_canonical))
+    }
+}
+
+impl<'a> NormalizationData<'a>
+{
+    /// проверить целостность данных форм нормализации, таблицы которых есть в наборе
+    pub fn validate(&self) -> Result<(), DataError>
+    {
+        match self.has_nfc() {
+            true => validate_composition(&self.nfd, &self.compositions, &self.nfc_expansions)?,
+            false if self.has_nfd() => validate_decomposition(&self.nfd)?,
+            false => (),
+        }
+
+        match self.has_nfkc() {
+            true => validate_composition(&self.nfkd, &self.compositions, &self.nfkc_expansions),
+            false if self.has_nfkd() => validate_decomposition(&self.nfkd),
+            false => Ok(()),
+        }
+    }
+}
+
+/// блок данных проверен
+const BLOCK_CHECKED: u8 = 1;
+/// в блоке есть отметка слога хангыль
+const BLOCK_HANGUL: u8 = 2;
+/// в блоке есть кодпоинт, не проходящий быструю проверку NF(K)C
+const BLOCK_FAILS_QUICK_CHECK: u8 = 4;
+/// в блоке есть кодпоинт, не являющийся стартером без декомпозиции
+const BLOCK_DECOMPOSES: u8 = 8;
+
+/// данные композиции, проверяемые вместе с данными декомпозиции
+struct CompositionCheck<'c, 'a>
+{
+    compositions: &'c CompositionData<'a>,
+    expansions_patch: &'c ExpansionsPatch<'a>,
+    /// для каждого начала группы вариантов комбинирования - количество вариантов, уже проверенных
+    /// на доступность по хешу
+    reachable: Vec<u8>,
+}
+
+/// проверка данных декомпозиции: индекс и блоки данных находятся в пределах таблиц, декомпозиции
+/// ссылаются на существующие элементы расширений и состоят из допустимых кодпоинтов
+fn validate_decomposition(data: &DecompositionData) -> Result<(), DataError>
+{
+    validate(data, None)
+}
+
+/// проверка данных композиции: помимо данных декомпозиции проверяются бит быстрой проверки,
+/// информация о комбинировании, замена расширений NF(K)C и таблица композиций
+fn validate_composition(
+    data: &DecompositionData,
+    compositions: &CompositionData,
+    expansions_patch: &ExpansionsPatch,
+) -> Result<(), DataError>
+{
+    let table = compositions.compositions;
+
+    let mut check = CompositionCheck {
+        compositions,
+        expansions_patch,
+        reachable: vec![0; table.len()],
+    };
+
+    validate(data, Some(&mut check))?;
+
+    for (index, &entry) in table.iter().enumerate() {
+        let composite = (entry >> 18) as u32 & 0x3FFFF;
+
+        if !is_scalar(composite) {
+            return Err(DataError::InvalidComposition {
+                index,
+                reason: "composite is not a scalar value",
+            });
+        }
+
+        check
+            .check_combining((entry >> 48) as u32)
+            .map_err(|reason| DataError::InvalidComposition { index, reason })?;
+    }
+
+    Ok(())
+}
+
+/// структура индекса проверяется для каждого блока из 8 кодпоинтов, каждый блок данных - один раз,
+/// при первом обращении к нему. отдельные кодпоинты проверяются, только если в блоке данных есть
+/// значения, допустимые не для любого кодпоинта (слоги хангыль, непрошедшие быструю проверку)
+fn validate(
+    data: &DecompositionData,
+    mut composition: Option<&mut CompositionCheck>,
+) -> Result<(), DataError>
+{
+    if data.quick_check.len() != 128 {
+        return Err(invalid(0x80, "quick check table has invalid length"));
+    }
+
+    // 4-байтовые последовательности быстрая проверка не пропускает
+    if data.quick_check[48 .. 64]
+        .iter()
+        .chain(&data.quick_check[112 ..])
+        .any(|&element| element != 0)
+    {
+        return Err(invalid(
+            0x10000,
+            "quick check table passes 4-byte sequences",
+        ));
+    }
+
+    if data.quick_check_index.len() != 2048 {
+        return Err(invalid(0x800, "quick check index has invalid length"));
+    }
+
+    if let Some(position) = data
+        .quick_check_index
+        .iter()
+        .position(|&row| row as usize >= data.quick_check_rows.len())
+    {
+        return Err(invalid(
+            ((position & 0x3FF) << 6) as u32,
+            "quick check row out of range",
+        ));
+    }
+
+    // после последнего блока индекса данные есть только у непрерывной части
+    let last = (((LAST_DECOMPOSING_CODEPOINT_BLOCK as u32 + 1) << 7) - 1)
+        .max(data.continuous_block_end.min(LAST_CODEPOINT));
+
+    let mut blocks = vec![0u8; data.data.len().div_ceil(8)];
+    let mut first = 0;
+
+    while first <= last {
+        // блок из 8 кодпоинтов, который может быть разделён концом непрерывной части
+        let end = match first <= data.continuous_block_end {
+            true => (first | 7).min(data.continuous_block_end),
+            false => first | 7,
+        };
+
+        let block = data_block(data, first).ok_or(invalid(first, "index out of range"))?;
+
+        if (block | 7) as usize >= data.data.len() {
+            return Err(invalid(first, "data block out of range"));
+        }
+
+        let position = (block >> 3) as usize;
+
+        if blocks[position] & BLOCK_CHECKED == 0 {
+            let entries = &data.data[position << 3 .. (position << 3) + 8];
+
+            blocks[position] = check_block(data, entries, first & !7, composition.as_deref_mut())?;
+        }
+
+        let flags = blocks[position];
+
+        let needs_codepoints = flags & BLOCK_HANGUL != 0
+            || (0x80 .. 0x10000).contains(&first)
+                && flags & (BLOCK_FAILS_QUICK_CHECK | BLOCK_DECOMPOSES) != 0;
+
+        if needs_codepoints {
+            for code in first ..= end {
+                check_codepoint(data, code, data.data[(block | (code as u16 & 7)) as usize])?;
+            }
+        }
+
+        first = end + 1;
+    }
+
+    Ok(())
+}
+
+/// проверка значений блока данных, не зависящих от кодпоинта. code - первый кодпоинт, ссылающийся
+/// на блок (для сообщения об ошибке). возвращает признаки блока
+fn check_block(
+    data: &DecompositionData,
+    entries: &[u32],
+    code: u32,
+    mut composition: Option<&mut CompositionCheck>,
+) -> Result<u8, DataError>
+{
+    let mut flags = BLOCK_CHECKED;
+
+    for (offset, &value) in entries.iter().enumerate() {
+        let code = code | offset as u32;
+
+        check_value(data, value).map_err(|reason| invalid(code, reason))?;
+
+        if let Some(composition) = composition.as_deref_mut() {
+            composition
+                .check_value(data, value)
+                .map_err(|reason| invalid(code, reason))?;
+        }
+
+        if (value as u8) >> 1 == MARKER_HANGUL_SYLLABLE {
+            flags |= BLOCK_HANGUL;
+        }
+        if value & 1 != 0 {
+            flags |= BLOCK_FAILS_QUICK_CHECK;
+        }
+        if value & 0xFC != 0 {
+            flags |= BLOCK_DECOMPOSES;
+        }
+    }
+
+    Ok(flags)
+}
+
+/// значение данных декомпозиции: декомпозиции ссылаются на существующие элементы расширений
+/// и состоят из допустимых кодпоинтов
+fn check_value(data: &DecompositionData, value: u32) -> Result<(), &'static str>
+{
+    match (value as u8) >> 1 {
+        MARKER_STARTER | MARKER_COMBINES_BACKWARDS | MARKER_HANGUL_SYLLABLE => Ok(()),
+        MARKER_NONSTARTER => match (value >> 8) as u8 {
+            0 => Err("nonstarter with zero CCC"),
+            _ => Ok(()),
+        },
+        MARKER_SINGLETON => match is_scalar(value >> 8) {
+            true => Ok(()),
+            false => Err("singleton is not a scalar value"),
+        },
+        MARKER_EXPANSION => expansion(data.expansions, value, value >> 18),
+        MARKER_EXPANSION_COMBINED_PATCH | MARKER_EXPANSION_COMBINED_EMPTY => {
+            // первый элемент - информация о комбинировании для NF(K)C, декомпозиция следует за ним
+            expansion(data.expansions, value, (value >> 18) + 1)
+        }
+        _ => match is_scalar(value >> 16) {
+            true => Ok(()),
+            false => Err("pair nonstarter is not a scalar value"),
+        },
+    }
+}
+
+/// значения, допустимые не для любого кодпоинта: отметка слога хангыль и кодпоинты, пропускаемые
+/// таблицей или строками быстрой проверки: они проходят быструю проверку NF(K)C или являются
+/// стартерами без декомпозиции (NF(K)D)
+fn check_codepoint(data: &DecompositionData, code: u32, value: u32) -> Result<(), DataError>
+{
+    if (value as u8) >> 1 == MARKER_HANGUL_SYLLABLE && !HANGUL_SYLLABLES.contains(&code) {
+        return Err(invalid(
+            code,
+            "Hangul syllable marker outside of Hangul syllables",
+        ));
+    }
+
+    if !(0x80 .. 0x10000).contains(&code) {
+        return Ok(());
+    }
+
+    // элемент и бит таблицы быстрой проверки: у 2-байтовых последовательностей - кодпоинт,
+    // у 3-байтовых - блок из 64 кодпоинтов
+    let (element, bit) = match code < 0x800 {
+        true => ((code >> 6) as usize, code & 0x3F),
+        false => (32 + (code >> 12) as usize, (code >> 6) & 0x3F),
+    };
+
+    // 3-байтовые последовательности проверяются и по строкам
+    let passes = |form: usize| {
+        (data.quick_check[form * 64 + element] >> bit) & 1 != 0
+            || code >= 0x800
+                && (data.quick_check_rows
+                    [data.quick_check_index[form * 1024 + (code >> 6) as usize] as usize]
+                    >> (code & 0x3F))
+                    & 1
+                    != 0
+    };
+
+    if (passes(0) && value & 1 != 0) || (passes(1) && value & 0xFC != 0) {
+        return Err(invalid(
+            code,
+            "quick check table passes code point failing quick check",
+        ));
+    }
+
+    Ok(())
+}
+
+impl<'c, 'a> CompositionCheck<'c, 'a>
+{
+    /// значение данных композиции: бит быстрой проверки соответствует отметке, информация
+    /// о комбинировании указывает на группу таблицы композиций, замена расширений существует
+    fn check_value(&mut self, data: &DecompositionData, value: u32) -> Result<(), &'static str>
+    {
+        let marker = (value as u8) >> 1;
+
+        // стартеры без декомпозиции в NF(K)C проходят быструю проверку, синглтоны, нестартеры и
+        // стартеры, комбинируемые с предыдущими, - нет
+        let expected_quick_check = match marker {
+            MARKER_STARTER | MARKER_HANGUL_SYLLABLE | MARKER_EXPANSION_COMBINED_EMPTY => Some(0),
+            MARKER_NONSTARTER | MARKER_SINGLETON | MARKER_COMBINES_BACKWARDS => Some(1),
+            _ => None,
+        };
+
+        if expected_quick_check.is_some_and(|bit| value & 1 != bit) {
+            return Err("quick check bit is inconsistent with marker");
+        }
+
+        // значение стартера, на который ссылается декомпозиция: его блок может быть ещё не проверен
+        let dec_value = |code: u32| lookup(data, code).ok_or("index out of range");
+
+        let combining = match marker {
+            MARKER_STARTER | MARKER_HANGUL_SYLLABLE | MARKER_COMBINES_BACKWARDS => value >> 16,
+            MARKER_NONSTARTER => return Ok(()),
+            MARKER_SINGLETON => dec_value(value >> 8)? >> 16,
+            MARKER_EXPANSION => {
+                // расширение уже проверено
+                let last_starter = (value >> 8) & 0x1F;
+                let entry = data.expansions[((value >> 18) + last_starter) as usize];
+
+                dec_value(entry >> 8)? >> 16
+            }
+            MARKER_EXPANSION_COMBINED_PATCH => {
+                let info = data.expansions[(value >> 18) as usize];
+
+                let last_starter = info & 0x7;
+                let count = (info >> 3) & 0x7;
+                let index = (info >> 6) & 0x3FF;
+
+                let expansions = self
+                    .expansions_patch
+                    .expansions
+                    .get(index as usize .. (index + count) as usize)
+                    .ok_or("expansion patch out of range")?;
+
+                if last_starter > count {
+                    return Err("invalid expansion starter position");
+                }
+
+                if !expansions.iter().all(|&entry| is_scalar(entry >> 8)) {
+                    return Err("expansion contains invalid code point");
+                }
+
+                info >> 16
+            }
+            MARKER_EXPANSION_COMBINED_EMPTY => data.expansions[(value >> 18) as usize] & 0xFFFF,
+            _ => dec_value(((value as u16) >> 1) as u32)? >> 16,
+        };
+
+        self.check_combining(combining)
+    }
+
+    /// варианты комбинирования находятся в пределах таблицы композиций и доступны по хешу.
+    /// доступность проверяется один раз для каждой группы
+    fn check_combining(&mut self, combining: u32) -> Result<(), &'static str>
+    {
+        if !is_combining_valid(self.compositions.compositions, combining) {
+            return Err("combining index out of range");
+        }
+
+        let combining = Combining::from(combining as u16);
+
+        // пустая группа может начинаться сразу за концом таблицы
+        let checked = match self.reachable.get_mut(combining.index() as usize) {
+            Some(checked) => checked,
+            None => return Ok(()),
+        };
+
+        if combining.count() as u8 <= *checked {
+            return Ok(());
+        }
+
+        match is_combining_reachable(self.compositions, combining) {
+            true => {
+                *checked = combining.count() as u8;
+                Ok(())
+            }
+            false => Err("composition is not reachable by hash"),
+        }
+    }
+}
+
+/// декомпозиция, вынесенная в таблицу расширений: элементы в пределах таблицы, последний стартер
+/// указывает на элемент декомпозиции, все элементы - допустимые кодпоинты
+fn expansion(expansions: &[u32], dec_value: u32, index: u32) -> Result<(), &'static str>
+{
+    let last_starter = (dec_value >> 8) & 0x1F;
+    let count = (dec_value >> 13) & 0x1F;
+
+    let entries = expansions
+        .get(index as usize .. (index + count) as usize)
+        .ok_or("expansion out of range")?;
+
+    if last_starter >= count {
+        return Err("invalid expansion starter position");
+    }
+
+    match entries.iter().all(|&entry| is_scalar(entry >> 8)) {
+        true => Ok(()),
+        false => Err("expansion contains invalid code point"),
+    }
+}
+
+/// данные о декомпозиции символа с проверкой границ таблиц
+fn lookup(data: &DecompositionData, code: u32) -> Option<u32>
+{
+    // все кодпоинты, следующие за последним блоком индекса, не имеют декомпозиции
+    if code > data.continuous_block_end && (code >> 7) > LAST_DECOMPOSING_CODEPOINT_BLOCK as u32 {
+        return Some(0);
+    }
+
+    let block = data_block(data, code)?;
+
+    data.data
+        .get((block | (code as u16) & 0x7) as usize)
+        .copied()
+}
+
+/// начало блока данных кодпоинта с проверкой границ индекса
+fn data_block(data: &DecompositionData, code: u32) -> Option<u16>
+{
+    let data_block_base = match code <= data.continuous_block_end {
+        true => 0x600 | (((code >> 3) as u16) & !0xF),
+        false => *data.index.get((code >> 7) as usize)?,
+    };
+
+    let code_offsets = (code as u16) & 0x7F;
+
+    data.index
+        .get((data_block_base | (code_offsets >> 3)) as usize)
+        .copied()
+}
+
+/// варианты комбинирования находятся в пределах таблицы композиций
+fn is_combining_valid(compositions: &[u64], combining: u32) -> bool
+{
+    let combining = Combining::from(combining as u16);
+
+    (combining.index() + combining.count()) as usize <= compositions.len()
+}
+
+/// все записи группы вариантов комбинирования находятся по хешу пары (начало группы, второй
+/// кодпоинт). группа уже проверена на нахождение в пределах таблицы
+fn is_combining_reachable(compositions: &CompositionData, combining: Combining) -> bool
+{
+    let first = combining.index() as usize;
+
+    (first .. first + combining.count() as usize).all(|index| {
+        let second = compositions.compositions[index] as u32 & 0x3FFFF;
+
+        composition_index(combining, second, compositions) == Some(index)
+    })
+}
+
+/// допустимое значение char
+fn is_scalar(code: u32) -> bool
+{
+    char::from_u32(code).is_some()
+}
+
+fn invalid(code: u32, reason: &'static str) -> DataError
+{
+    DataError::InvalidCodepoint { code, reason }
+}
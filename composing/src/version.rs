@@ -0,0 +1,35 @@
+use core::fmt;
+
+/// версия Unicode, которой соответствуют данные нормализации
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnicodeVersion
+{
+    pub major: u8,
+    pub minor: u8,
+    pub update: u8,
+}
+
+impl UnicodeVersion
+{
+    /// Unicode 15.1.0 - последняя версия запечённых данных
+    pub const V15_1: Self = Self::new(15, 1, 0);
+    /// Unicode 14.0.0 - возможность unicode_14_0
+    pub const V14_0: Self = Self::new(14, 0, 0);
+
+    pub const fn new(major: u8, minor: u8, update: u8) -> Self
+    {
+        Self {
+            major,
+            minor,
+            update,
+        }
+    }
+}
+
+impl fmt::Display for UnicodeVersion
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.update)
+    }
+}
@@ -0,0 +1,213 @@
+use unicode_composing::{
+    ComposingNormalizer, CompositionData, DataError, DecomposingNormalizer, ExpansionsPatch,
+    NormalizationData, UnicodeVersion,
+};
+
+/// данные в бинарном формате, выровненные по 8 байтам
+fn aligned(bytes: &[u8]) -> Vec<u64>
+{
+    let mut storage = vec![0u64; bytes.len().div_ceil(8)];
+
+    as_bytes_mut(&mut storage)[.. bytes.len()].copy_from_slice(bytes);
+
+    storage
+}
+
+fn as_bytes_mut(storage: &mut [u64]) -> &mut [u8]
+{
+    unsafe { std::slice::from_raw_parts_mut(storage.as_mut_ptr() as *mut u8, storage.len() * 8) }
+}
+
+fn as_bytes(storage: &[u64], length: usize) -> &[u8]
+{
+    unsafe { std::slice::from_raw_parts(storage.as_ptr() as *const u8, length) }
+}
+
+/// нормализаторы из бинарных данных совпадают с запечёнными
+#[test]
+fn binary_roundtrip()
+{
+    let bytes = NormalizationData::baked().to_bytes();
+    let storage = aligned(&bytes);
+    let blob = as_bytes(&storage, bytes.len());
+
+    let data = NormalizationData::from_bytes(blob).unwrap();
+
+    assert_eq!(data.unicode_version, UnicodeVersion::V15_1);
+    assert_eq!(data.to_bytes(), bytes);
+
+    let forms = [
+        (
+            ComposingNormalizer::from_bytes(blob, true).unwrap(),
+            ComposingNormalizer::nfc(),
+        ),
+        (
+            ComposingNormalizer::from_bytes(blob, false).unwrap(),
+            ComposingNormalizer::nfkc(),
+        ),
+    ];
+
+    let decomposing_forms = [
+        (
+            DecomposingNormalizer::from_bytes(blob, true).unwrap(),
+            DecomposingNormalizer::nfd(),
+        ),
+        (
+            DecomposingNormalizer::from_bytes(blob, false).unwrap(),
+            DecomposingNormalizer::nfkd(),
+        ),
+    ];
+
+    for data in crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files())
+    {
+        let text = data.1.as_str();
+
+        for (loaded, baked) in forms.iter() {
+            assert_eq!(loaded.normalize(text), baked.normalize(text), "{}", data.0);
+        }
+
+        for (loaded, baked) in decomposing_forms.iter() {
+            assert_eq!(loaded.normalize(text), baked.normalize(text), "{}", data.0);
+        }
+    }
+}
+
+/// данные сборки с единственной формой нормализации: таблицы отключённых форм экспортируются
+/// пустыми (см. baked_table! в composing/src/data.rs), такие данные загружаются обратно
+#[test]
+fn binary_roundtrip_single_form()
+{
+    let baked = NormalizationData::baked();
+
+    let mut nfc = baked;
+    nfc.nfkd.index = &[];
+    nfc.nfkd.data = &[];
+    nfc.nfkd.expansions = &[];
+    nfc.nfkd.quick_check = &[];
+    nfc.nfkd.quick_check_index = &[];
+    nfc.nfkd.quick_check_rows = &[];
+    nfc.nfkc_expansions = ExpansionsPatch { expansions: &[] };
+
+    let mut nfd = nfc;
+    nfd.compositions = CompositionData {
+        compositions: &[],
+        displacements: &[],
+        slots: &[],
+    };
+    nfd.nfc_expansions = ExpansionsPatch { expansions: &[] };
+
+    for (exported, has_nfc) in [(nfc, true), (nfd, false)] {
+        let bytes = exported.to_bytes();
+        let storage = aligned(&bytes);
+        let blob = as_bytes(&storage, bytes.len());
+
+        let data = NormalizationData::from_bytes(blob).unwrap();
+
+        assert_eq!(data.to_bytes(), bytes);
+        assert_eq!(data.has_nfc(), has_nfc);
+        assert!(data.has_nfd() && !data.has_nfkc() && !data.has_nfkd());
+
+        assert_eq!(
+            data.composing(false).map(|_| ()),
+            Err(DataError::MissingForm("NFKC"))
+        );
+        assert_eq!(
+            data.decomposing(false).map(|_| ()),
+            Err(DataError::MissingForm("NFKD"))
+        );
+
+        let loaded = data.decomposing(true).unwrap();
+
+        for (name, text) in crate::data::files() {
+            assert_eq!(
+                loaded.normalize(&text),
+                DecomposingNormalizer::nfd().normalize(&text),
+                "{}",
+                name
+            );
+        }
+
+        match has_nfc {
+            true => {
+                let loaded = ComposingNormalizer::from_bytes(blob, true).unwrap();
+
+                for (name, text) in crate::data::files() {
+                    assert_eq!(
+                        loaded.normalize(&text),
+                        ComposingNormalizer::nfc().normalize(&text),
+                        "{}",
+                        name
+                    );
+                }
+            }
+            false => assert_eq!(
+                ComposingNormalizer::from_bytes(blob, true).map(|_| ()),
+                Err(DataError::MissingForm("NFC"))
+            ),
+        }
+    }
+}
+
+/// повреждённые данные
+#[test]
+fn binary_errors()
+{
+    let bytes = NormalizationData::baked().to_bytes();
+    let length = bytes.len();
+
+    let load = |bytes: &[u8]| {
+        let storage = aligned(bytes);
+        NormalizationData::from_bytes(as_bytes(&storage, bytes.len())).map(|_| ())
+    };
+
+    assert_eq!(load(&bytes), Ok(()));
+
+    assert_eq!(
+        load(&bytes[.. 10]),
+        Err(DataError::InvalidLength {
+            expected: 96,
+            actual: 10
+        })
+    );
+    assert_eq!(
+        load(&bytes[.. length - 1]),
+        Err(DataError::InvalidLength {
+            expected: length,
+            actual: length - 1
+        })
+    );
+
+    let mut broken = bytes.clone();
+    broken[0] = b'X';
+    assert_eq!(load(&broken), Err(DataError::InvalidMagic));
+
+    let mut broken = bytes.clone();
+    broken[8] = 3;
+    assert_eq!(load(&broken), Err(DataError::UnsupportedFormatVersion(3)));
+
+    let mut broken = bytes.clone();
+    broken[length / 2] ^= 1;
+    assert!(matches!(
+        load(&broken),
+        Err(DataError::ChecksumMismatch { .. })
+    ));
+
+    // длина таблицы в заголовке не соответствует данным
+    let mut broken = bytes.clone();
+    broken[24] += 1;
+    assert!(matches!(
+        load(&broken),
+        Err(DataError::InvalidLength { .. })
+    ));
+
+    // данные, не выровненные по 8 байтам
+    let mut storage = aligned(&[&[0], bytes.as_slice()].concat());
+    let shifted = &as_bytes_mut(&mut storage)[1 .. length + 1];
+
+    assert_eq!(
+        NormalizationData::from_bytes(shifted).map(|_| ()),
+        Err(DataError::Misaligned)
+    );
+}
@@ -0,0 +1,143 @@
+use unicode_composing::{ComposingNormalizer, DecomposingNormalizer, NormalizationData};
+
+use crate::allocations::allocations;
+
+static NFC: ComposingNormalizer = ComposingNormalizer::new_nfc();
+static NFKC: ComposingNormalizer = ComposingNormalizer::new_nfkc();
+static NFD: DecomposingNormalizer = DecomposingNormalizer::new_nfd();
+static NFKD: DecomposingNormalizer = DecomposingNormalizer::new_nfkd();
+
+/// нормализаторы, созданные на этапе компиляции, совпадают с результатами ICU
+#[test]
+fn construction_static()
+{
+    for data in crate::data::decomposed_files() {
+        assert_icu!((NFC, NFKC, NFD, NFKD), data.1.as_str(), "{}", data.0);
+    }
+}
+
+/// создание нормализатора не обращается к куче
+#[test]
+fn construction_without_allocations()
+{
+    let before = allocations();
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+    let nfd = DecomposingNormalizer::new_nfd();
+    let nfkd = DecomposingNormalizer::new_nfkd();
+
+    assert_eq!(allocations(), before);
+
+    let mut out = [0; 16];
+
+    assert_eq!(nfc.normalize_into_slice("A\u{30A}", &mut out), Ok(2));
+    assert_eq!(nfkc.normalize_into_slice("\u{FB01}", &mut out), Ok(2));
+    assert_eq!(allocations(), before);
+
+    assert_eq!(nfd.normalize("\u{C5}"), "A\u{30A}");
+    assert_eq!(nfkd.normalize("\u{FB01}"), "fi");
+}
+
+/// общие нормализаторы, клонирование и нормализаторы по умолчанию
+#[test]
+fn construction_shared()
+{
+    assert!(std::ptr::eq(
+        ComposingNormalizer::nfc(),
+        ComposingNormalizer::nfc()
+    ));
+    assert!(std::ptr::eq(
+        DecomposingNormalizer::nfkd(),
+        DecomposingNormalizer::nfkd()
+    ));
+
+    let before = allocations();
+
+    let nfc = ComposingNormalizer::nfc().clone();
+    let nfkc = ComposingNormalizer::nfkc().clone();
+    let nfd = DecomposingNormalizer::nfd().clone();
+    let nfkd = DecomposingNormalizer::nfkd().clone();
+    let default_composing = ComposingNormalizer::default();
+    let default_decomposing = DecomposingNormalizer::default();
+
+    assert_eq!(allocations(), before);
+
+    // нормализаторы из разных потоков
+    let handles: Vec<_> = (0 .. 4)
+        .map(|_| std::thread::spawn(|| ComposingNormalizer::nfc().normalize("A\u{30A}")))
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), "\u{C5}");
+    }
+
+    for data in crate::data::decomposed_files() {
+        let text = data.1.as_str();
+
+        assert_eq!(nfc.normalize(text), NFC.normalize(text), "nfc, {}", data.0);
+        assert_eq!(
+            nfkc.normalize(text),
+            NFKC.normalize(text),
+            "nfkc, {}",
+            data.0
+        );
+        assert_eq!(nfd.normalize(text), NFD.normalize(text), "nfd, {}", data.0);
+        assert_eq!(
+            nfkd.normalize(text),
+            NFKD.normalize(text),
+            "nfkd, {}",
+            data.0
+        );
+        assert_eq!(
+            default_composing.normalize(text),
+            NFC.normalize(text),
+            "{}",
+            data.0
+        );
+        assert_eq!(
+            default_decomposing.normalize(text),
+            NFD.normalize(text),
+            "{}",
+            data.0
+        );
+    }
+}
+
+/// все четыре формы нормализации из одного набора данных
+#[test]
+fn construction_bundle()
+{
+    static DATA: NormalizationData = NormalizationData::baked();
+
+    let before = allocations();
+
+    let nfc = DATA.nfc();
+    let nfkc = DATA.nfkc();
+    let nfd = DATA.nfd();
+    let nfkd = DATA.nfkd();
+
+    assert_eq!(allocations(), before);
+
+    for data in crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files())
+    {
+        let text = data.1.as_str();
+
+        assert_eq!(nfc.normalize(text), NFC.normalize(text), "nfc, {}", data.0);
+        assert_eq!(
+            nfkc.normalize(text),
+            NFKC.normalize(text),
+            "nfkc, {}",
+            data.0
+        );
+        assert_eq!(nfd.normalize(text), NFD.normalize(text), "nfd, {}", data.0);
+        assert_eq!(
+            nfkd.normalize(text),
+            NFKD.normalize(text),
+            "nfkd, {}",
+            data.0
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::ComposingNormalizer;
+use unicode_data::{NormalizationTest, NORMALIZATION_TESTS};
+
+use crate::allocations::allocations;
+
+/// нормализация без копирования совпадает с результатом ICU, уже нормализованные строки
+/// не обращаются к куче
+#[test]
+fn cow_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let text = data.1.as_str();
+
+            let before = allocations();
+            let normalized = my.normalize_cow(text);
+            let after = allocations();
+
+            if let Cow::Borrowed(_) = normalized {
+                assert_eq!(before, after, "{}, {}", form, data.0);
+            }
+
+            assert_eq!(normalized, icu.normalize(text), "{}, {}", form, data.0);
+            assert_eq!(
+                matches!(normalized, Cow::Borrowed(_)),
+                icu.is_normalized(text),
+                "{}, {}",
+                form,
+                data.0
+            );
+        }
+    }
+}
+
+/// уже нормализованные строки из тестов UCD не копируются
+#[test]
+fn cow_ucd()
+{
+    let tests: &Vec<NormalizationTest> = &NORMALIZATION_TESTS;
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+
+    for t in tests {
+        for source in [&t.c1, &t.c2, &t.c3, &t.c4, &t.c5] {
+            assert_eq!(
+                nfc.normalize_cow(source),
+                nfc.normalize(source),
+                "nfc, {}",
+                t.line
+            );
+            assert_eq!(
+                nfkc.normalize_cow(source),
+                nfkc.normalize(source),
+                "nfkc, {}",
+                t.line
+            );
+        }
+
+        assert!(
+            matches!(nfc.normalize_cow(&t.c2), Cow::Borrowed(_)),
+            "nfc, {}",
+            t.line
+        );
+        assert!(
+            matches!(nfkc.normalize_cow(&t.c4), Cow::Borrowed(_)),
+            "nfkc, {}",
+            t.line
+        );
+    }
+}
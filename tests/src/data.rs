@@ -4,7 +4,19 @@ use std::io::Read;
 /// данные на разных языках для тестов
 pub fn files() -> Vec<(String, String)>
 {
-    let dir = fs::read_dir("./../test_data/texts").unwrap();
+    read_dir("./../test_data/texts")
+}
+
+/// данные на разных языках в NFD
+pub fn decomposed_files() -> Vec<(String, String)>
+{
+    read_dir("./../test_data/texts_decomposed")
+}
+
+/// прочитать папку с текстами
+fn read_dir(dir: &str) -> Vec<(String, String)>
+{
+    let dir = fs::read_dir(dir).unwrap();
 
     let mut data = vec![];
 
@@ -0,0 +1,105 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use icu_normalizer::DecomposingNormalizer as icu_dec;
+use unicode_composing::{ComposingNormalizer, IsNormalized};
+
+/// символы, на которых прерывается пропуск блоков: первые байты на границе быстрой проверки
+/// и около неё, нестартеры, композиции, синглтоны, 3- и 4-байтовые последовательности
+const BREAKING: &[&str] = &[
+    "\u{A0}",
+    "\u{C0}",
+    "\u{E9}",
+    "\u{BF}",
+    "\u{2FF}",
+    "\u{300}",
+    "\u{301}",
+    "e\u{301}",
+    "\u{416}",
+    "\u{1100}\u{1161}",
+    "\u{212B}",
+    "\u{AC00}",
+    "\u{1D15E}",
+    "\u{FB01}",
+];
+
+/// пропуск блоков байтов, меньших границы быстрой проверки: символы на разных позициях
+/// относительно границ блоков SSE2 / AVX2
+#[test]
+fn fast_path()
+{
+    let icu_nfc = icu::new_nfc();
+    let nfc = ComposingNormalizer::nfc();
+
+    // ASCII и 2-байтовые символы, прошедшие быструю проверку для всех форм
+    for filler in ["a", "\u{7F}", "\u{80}"] {
+        for breaking in BREAKING {
+            for before in 0 .. 70 {
+                for after in [0, 1, 15, 16, 17, 31, 32, 33, 64] {
+                    let text = filler.repeat(before) + breaking + &filler.repeat(after) + breaking;
+                    let text = text.as_str();
+
+                    assert_icu!(text, "{:?}", text);
+
+                    assert_eq!(
+                        nfc.is_normalized(text),
+                        icu_nfc.is_normalized(text),
+                        "nfc is_normalized: {:?}",
+                        text
+                    );
+
+                    if icu_nfc.is_normalized(text) {
+                        assert_ne!(
+                            nfc.quick_check(text),
+                            IsNormalized::No,
+                            "nfc quick check: {:?}",
+                            text
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 2-байтовые последовательности (U+0080 ..= U+07FF), проверяемые по битовой карте: в начале,
+/// в середине и в конце блоков SSE2
+#[test]
+fn two_bytes()
+{
+    for code in 0x80 .. 0x800 {
+        let c = char::from_u32(code).unwrap();
+
+        for before in ["", "a", "ж", "ab", "жa", "\u{5D0}\u{5D1} "] {
+            for repeat in [1, 7, 8, 9, 16, 17] {
+                let text = String::from(before) + &format!("ж{}a", c).repeat(repeat) + "\u{301}";
+                let text = text.as_str();
+
+                assert_icu!(text, "{:?}", text);
+            }
+        }
+    }
+}
+
+/// 3-байтовые последовательности (U+0800 ..= U+FFFF), пропускаемые по таблице блоков и по строкам
+/// отдельных последовательностей: символы и их канонические декомпозиции (композиция внутри блока)
+/// рядом с символами из пропускаемых и непропускаемых блоков
+#[test]
+fn three_bytes()
+{
+    for c in (0x800 .. 0x10000).filter_map(char::from_u32) {
+        let c = String::from(c);
+        let decomposed = icu_dec::new_nfd().normalize(&c);
+
+        for c in [c.as_str(), decomposed.as_str()] {
+            for before in ["", "a", "\u{4E2D}", "\u{93C}"] {
+                for repeat in [1, 9] {
+                    let text = String::from(before)
+                        + &format!("\u{4E2D}{}a", c).repeat(repeat)
+                        + "\u{301}";
+                    let text = text.as_str();
+
+                    assert_icu!(text, "{:?}", text);
+                }
+            }
+        }
+    }
+}
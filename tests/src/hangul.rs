@@ -0,0 +1,79 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::ComposingNormalizer;
+
+/// чамо и слоги хангыль: ведущие согласные (L), гласные (V), завершающие согласные (T), в том числе
+/// на границах комбинируемых диапазонов, слоги LV и LVT, а также соседние с ними символы
+const CONJOINING: &[char] = &[
+    '\u{1100}', '\u{1112}', '\u{1113}', '\u{115F}', '\u{1160}', '\u{1161}', '\u{116C}', '\u{1175}',
+    '\u{1176}', '\u{11A7}', '\u{11A8}', '\u{11AA}', '\u{11C2}', '\u{11C3}', '\u{AC00}', '\u{AC01}',
+    '\u{AC1C}', '\u{D788}', '\u{D7A3}', '\u{D7A4}', '\u{D7B0}', '\u{D7CB}', 'a', '\u{4E2D}',
+    '\u{301}', '\u{302E}',
+];
+
+/// символы совместимости, декомпозиция которых содержит чамо: чамо совместимости, полуширинные
+/// чамо, чамо и слоги в скобках и в кругах
+fn compatibility() -> impl Iterator<Item = char>
+{
+    [
+        0x3131 ..= 0x318E,
+        0xFFA0 ..= 0xFFDC,
+        0x3200 ..= 0x321E,
+        0x3260 ..= 0x327E,
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(char::from_u32)
+}
+
+/// нормализация совпадает с результатом ICU для всех форм, в том числе для последовательностей кодпоинтов
+fn check(text: &str)
+{
+    assert_icu!(text, "{:?}", text);
+
+    let chars: Vec<char> = text.chars().collect();
+
+    for (my, icu, form) in [
+        (ComposingNormalizer::nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::nfkc(), icu::new_nfkc(), "nfkc"),
+    ] {
+        assert_eq!(
+            my.normalize_chars(&chars).into_iter().collect::<String>(),
+            icu.normalize(text),
+            "{} (chars): {:?}",
+            form,
+            text
+        );
+    }
+}
+
+/// последовательности чамо: пары и тройки символов, повторяющиеся последовательности
+#[test]
+fn hangul_conjoining()
+{
+    for &x in CONJOINING {
+        for &y in CONJOINING {
+            for &z in CONJOINING {
+                check(&String::from_iter([x, y, z]));
+            }
+
+            check(&String::from_iter([x, y]).repeat(20));
+        }
+    }
+}
+
+/// чамо совместимости комбинируются с соседними чамо и слогами (NFKC)
+#[test]
+fn hangul_compatibility()
+{
+    for c in compatibility() {
+        for &x in CONJOINING {
+            check(&String::from_iter([x, c]));
+            check(&String::from_iter([c, x]));
+            check(&String::from_iter([x, c, x]));
+        }
+
+        for d in compatibility() {
+            check(&String::from_iter([c, d]));
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use icu_normalizer::ComposingNormalizer as icu;
+use icu_normalizer::DecomposingNormalizer as icu_dec;
 use unicode_composing::ComposingNormalizer as my;
+use unicode_composing::DecomposingNormalizer as my_dec;
 
 /// сравниваем с результатами нормализации ICU
 #[test]
@@ -36,3 +38,39 @@ fn icu()
 
     test!((my, "my"));
 }
+
+/// сравниваем с результатами декомпозиции ICU
+#[test]
+fn icu_decomposing()
+{
+    let icu_nfd = icu_dec::new_nfd();
+    let icu_nfkd = icu_dec::new_nfkd();
+
+    macro_rules! test {
+        ($(($n: ident,  $t: expr)),+) => {
+            $(
+                let nfd = $n::new_nfd();
+                let nfkd = $n::new_nfkd();
+
+                for data in crate::data::files() {
+                    assert_eq!(
+                        nfd.normalize(data.1.as_str()),
+                        icu_nfd.normalize(data.1.as_str()),
+                        "nfd,  {} - {}",
+                        $t,
+                        data.0
+                    );
+                    assert_eq!(
+                        nfkd.normalize(data.1.as_str()),
+                        icu_nfkd.normalize(data.1.as_str()),
+                        "nfkd, {} - {}",
+                        $t,
+                        data.0
+                    );
+                }
+            )+
+        };
+    }
+
+    test!((my_dec, "my"));
+}
@@ -0,0 +1,72 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::{ComposingNormalizer, IntoSliceError};
+
+use crate::allocations::allocations;
+
+/// нормализация в буфер совпадает с результатом ICU и не обращается к куче
+#[test]
+fn into_slice_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let text = data.1.as_str();
+            let expected = icu.normalize(text);
+
+            let mut out = vec![0; expected.len()];
+
+            let before = allocations();
+            let length = my.normalize_into_slice(text, &mut out);
+            let after = allocations();
+
+            assert_eq!(before, after, "{}, {}", form, data.0);
+            assert_eq!(length, Ok(expected.len()), "{}, {}", form, data.0);
+            assert_eq!(out, expected.as_bytes(), "{}, {}", form, data.0);
+
+            // буфер меньше необходимого
+            let length = my.normalize_into_slice(text, &mut out[.. expected.len() / 2]);
+
+            assert_eq!(
+                length,
+                Err(IntoSliceError::NeedMore {
+                    required: expected.len()
+                }),
+                "{}, {}",
+                form,
+                data.0
+            );
+        }
+    }
+}
+
+/// слишком длинная последовательность нестартеров
+#[test]
+fn into_slice_sequence_too_long()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+    let mut out = [0; 1024];
+
+    let text = format!("a{}b", "\u{301}\u{316}".repeat(15));
+    let expected = nfc.normalize(&text);
+
+    assert_eq!(
+        nfc.normalize_into_slice(&text, &mut out),
+        Ok(expected.len())
+    );
+    assert_eq!(&out[.. expected.len()], expected.as_bytes());
+
+    let text = format!("a{}b", "\u{301}".repeat(40));
+
+    assert_eq!(
+        nfc.normalize_into_slice(&text, &mut out),
+        Err(IntoSliceError::SequenceTooLong)
+    );
+}
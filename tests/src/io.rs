@@ -0,0 +1,134 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::{ComposingNormalizer, NormalizingReader, NormalizingWriter};
+
+/// чтение и запись с нормализацией совпадают с результатом ICU
+#[test]
+fn io_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let text = data.1.as_str();
+            let expected = icu.normalize(text);
+
+            // буферы малых размеров разрывают UTF-8 последовательности
+            for capacity in [1, 2, 3, 5, 8192] {
+                let inner = BufReader::with_capacity(capacity, text.as_bytes());
+                let mut result = String::new();
+
+                NormalizingReader::new(my, inner)
+                    .read_to_string(&mut result)
+                    .unwrap();
+
+                assert_eq!(
+                    result, expected,
+                    "reader, {}, {}, {}",
+                    form, data.0, capacity
+                );
+
+                let mut writer = NormalizingWriter::new(my, Vec::new());
+
+                for chunk in text.as_bytes().chunks(capacity) {
+                    writer.write_all(chunk).unwrap();
+                }
+
+                let result = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+                assert_eq!(
+                    result, expected,
+                    "writer, {}, {}, {}",
+                    form, data.0, capacity
+                );
+            }
+        }
+    }
+}
+
+/// построчное чтение и копирование
+#[test]
+fn io_lines()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+
+    for data in crate::data::decomposed_files() {
+        let text = data.1.as_str();
+        let expected = nfc.normalize(text);
+
+        let reader = NormalizingReader::new(&nfc, BufReader::with_capacity(7, text.as_bytes()));
+        let lines: Vec<String> = reader.lines().map(|line| line.unwrap()).collect();
+
+        assert_eq!(lines, expected.lines().collect::<Vec<&str>>(), "{}", data.0);
+
+        let mut writer = NormalizingWriter::new(&nfc, Vec::new());
+        io::copy(&mut text.as_bytes(), &mut writer).unwrap();
+
+        assert_eq!(writer.finish().unwrap(), expected.as_bytes(), "{}", data.0);
+    }
+}
+
+/// невалидный UTF-8 - ошибка InvalidData
+#[test]
+fn io_invalid_utf8()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+
+    for bytes in [
+        &b"abc\xFFdef"[..],
+        b"\x80",
+        b"a\xCC",
+        b"\xE2\x82",
+        b"\xE2\x82a",
+        b"\xED\xA0\x80",
+    ] {
+        for capacity in [1, 2, 16] {
+            let inner = BufReader::with_capacity(capacity, bytes);
+            let error = NormalizingReader::new(&nfc, inner)
+                .read_to_end(&mut Vec::new())
+                .unwrap_err();
+
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{:?}", bytes);
+        }
+
+        let mut writer = NormalizingWriter::new(&nfc, Vec::new());
+        let error = match writer.write_all(bytes) {
+            Ok(()) => writer.finish().unwrap_err(),
+            Err(error) => error,
+        };
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData, "{:?}", bytes);
+    }
+}
+
+/// при удалении без finish придержанное окончание записывается
+#[test]
+fn io_writer_drop()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+    let mut result = Vec::new();
+
+    {
+        let mut writer = NormalizingWriter::new(&nfc, &mut result);
+        writer.write_all("e\u{301}".as_bytes()).unwrap();
+    }
+
+    assert_eq!(result, "\u{E9}".as_bytes());
+
+    // finish уже записал окончание - при удалении ничего не дописывается
+    let mut result = Vec::new();
+    let mut writer = NormalizingWriter::new(&nfc, &mut result);
+
+    writer.write_all("e\u{301}".as_bytes()).unwrap();
+    writer.finish().unwrap();
+
+    assert_eq!(result, "\u{E9}".as_bytes());
+}
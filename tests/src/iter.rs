@@ -0,0 +1,52 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::ComposingNormalizer;
+use unicode_data::{NormalizationTest, NORMALIZATION_TESTS};
+
+/// ленивая нормализация совпадает с результатом ICU
+#[test]
+fn iter_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let text = data.1.as_str();
+            let normalized: String = my.normalize_iter(text.chars()).collect();
+
+            assert_eq!(normalized, icu.normalize(text), "{}, {}", form, data.0);
+        }
+    }
+}
+
+/// тесты UCD для ленивой нормализации
+#[test]
+fn iter_ucd()
+{
+    let tests: &Vec<NormalizationTest> = &NORMALIZATION_TESTS;
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+
+    let normalize = |normalizer: &ComposingNormalizer, source: &String| -> String {
+        normalizer.normalize_iter(source.chars()).collect()
+    };
+
+    for t in tests {
+        for source in [&t.c1, &t.c2, &t.c3] {
+            assert_eq!(normalize(&nfc, source), t.c2, "nfc, {}", t.line);
+        }
+        for source in [&t.c4, &t.c5] {
+            assert_eq!(normalize(&nfc, source), t.c4, "nfc, {}", t.line);
+        }
+        for source in [&t.c1, &t.c2, &t.c3, &t.c4, &t.c5] {
+            assert_eq!(normalize(&nfkc, source), t.c4, "nfkc, {}", t.line);
+        }
+    }
+}
@@ -1,7 +1,103 @@
+/// нормализация текста совпадает с результатом ICU для всех форм: нормализаторы NFC, NFKC, NFD, NFKD
+/// (по умолчанию - общие нормализаторы крейта), текст и сообщение об ошибке, как в assert_eq!
+#[cfg(test)]
+macro_rules! assert_icu {
+    (($nfc: expr, $nfkc: expr, $nfd: expr, $nfkd: expr), $text: expr, $($message: tt)+) => {{
+        let text: &str = $text;
+
+        assert_eq!(
+            $nfc.normalize(text),
+            icu_normalizer::ComposingNormalizer::new_nfc().normalize(text),
+            "nfc, {}",
+            format_args!($($message)+)
+        );
+        assert_eq!(
+            $nfkc.normalize(text),
+            icu_normalizer::ComposingNormalizer::new_nfkc().normalize(text),
+            "nfkc, {}",
+            format_args!($($message)+)
+        );
+        assert_eq!(
+            $nfd.normalize(text),
+            icu_normalizer::DecomposingNormalizer::new_nfd().normalize(text),
+            "nfd, {}",
+            format_args!($($message)+)
+        );
+        assert_eq!(
+            $nfkd.normalize(text),
+            icu_normalizer::DecomposingNormalizer::new_nfkd().normalize(text),
+            "nfkd, {}",
+            format_args!($($message)+)
+        );
+    }};
+    ($text: expr, $($message: tt)+) => {
+        assert_icu!(
+            (
+                unicode_composing::ComposingNormalizer::nfc(),
+                unicode_composing::ComposingNormalizer::nfkc(),
+                unicode_composing::DecomposingNormalizer::nfd(),
+                unicode_composing::DecomposingNormalizer::nfkd()
+            ),
+            $text,
+            $($message)+
+        )
+    };
+}
+
 #[cfg(test)]
 mod ucd;
 
 #[cfg(test)]
 mod icu;
 
+#[cfg(test)]
+mod quick_check;
+
+#[cfg(test)]
+mod cow;
+
+#[cfg(test)]
+mod iter;
+
+#[cfg(test)]
+mod sink;
+#[cfg(test)]
+mod streaming;
+
+#[cfg(test)]
+mod io;
+
+#[cfg(test)]
+mod utf8;
+
+#[cfg(test)]
+mod utf16;
+
+#[cfg(test)]
+mod utf32;
+
+#[cfg(test)]
+mod into_slice;
+
+#[cfg(test)]
+mod construction;
+
+#[cfg(test)]
+mod allocations;
+
+#[cfg(test)]
+mod binary;
+
+#[cfg(test)]
+mod validation;
+
+#[cfg(test)]
+mod version;
+
+#[cfg(test)]
+mod fast_path;
+
+#[cfg(test)]
+mod hangul;
+
 pub mod data;
@@ -0,0 +1,138 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::{ComposingNormalizer, IsNormalized};
+use unicode_data::{NormalizationTest, NORMALIZATION_TESTS};
+
+use crate::allocations::allocations;
+
+/// проверка нормализации совпадает с результатом ICU и не обращается к куче
+#[test]
+fn quick_check_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let text = data.1.as_str();
+
+            let before = allocations();
+            let is_normalized = my.is_normalized(text);
+            let after = allocations();
+
+            assert_eq!(before, after, "{}, {}", form, data.0);
+
+            assert_eq!(
+                is_normalized,
+                icu.is_normalized(text),
+                "{}, {}",
+                form,
+                data.0
+            );
+
+            check(my, text, format!("{}, {}", form, data.0).as_str());
+        }
+    }
+}
+
+/// проверка нормализации строк из тестов UCD
+#[test]
+fn quick_check_ucd()
+{
+    let tests: &Vec<NormalizationTest> = &NORMALIZATION_TESTS;
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+
+    for t in tests {
+        for source in [&t.c1, &t.c2, &t.c3, &t.c4, &t.c5] {
+            check(
+                &nfc,
+                source,
+                format!("nfc, {} {}", t.line, t.description).as_str(),
+            );
+            check(
+                &nfkc,
+                source,
+                format!("nfkc, {} {}", t.line, t.description).as_str(),
+            );
+        }
+
+        assert!(
+            nfc.is_normalized(&t.c2),
+            "nfc, {} {}",
+            t.line,
+            t.description
+        );
+        assert!(
+            nfc.is_normalized(&t.c4),
+            "nfc, {} {}",
+            t.line,
+            t.description
+        );
+        assert!(
+            nfkc.is_normalized(&t.c4),
+            "nfkc, {} {}",
+            t.line,
+            t.description
+        );
+    }
+}
+
+/// нестартеры, не комбинируемые с предыдущими кодпоинтами (NF(K)C_QC = Yes), проходят быструю
+/// проверку, комбинируемые (NF(K)C_QC = Maybe) - нет
+#[test]
+fn quick_check_nonstarters()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+
+    for normalizer in [&nfc, &nfkc] {
+        // точки иврита, индийские вирамы
+        assert_eq!(normalizer.quick_check("\u{5D1}\u{5BC}"), IsNormalized::Yes);
+        assert_eq!(normalizer.quick_check("\u{915}\u{94D}"), IsNormalized::Yes);
+        // нарушен канонический порядок
+        assert_eq!(
+            normalizer.quick_check("\u{5D1}\u{5BC}\u{5B0}"),
+            IsNormalized::No
+        );
+        // комбинирующие знаки латиницы
+        assert_eq!(normalizer.quick_check("a\u{301}"), IsNormalized::Maybe);
+        assert_eq!(
+            normalizer.quick_check("\u{5D1}\u{301}"),
+            IsNormalized::Maybe
+        );
+    }
+
+    // все кодпоинты после стартера
+    for c in (0 ..= 0x10FFFF).filter_map(char::from_u32) {
+        let text = String::from_iter(['a', c]);
+
+        check(&nfc, &text, "nfc");
+        check(&nfkc, &text, "nfkc");
+    }
+}
+
+/// результаты проверок согласованы с нормализацией
+fn check(normalizer: &ComposingNormalizer, source: &str, message: &str)
+{
+    let normalized = normalizer.normalize(source) == source;
+    let up_to = normalizer.is_normalized_up_to(source);
+
+    assert_eq!(normalizer.is_normalized(source), normalized, "{}", message);
+    assert_eq!(up_to == source.len(), normalized, "{}", message);
+
+    let prefix = &source[.. up_to];
+    assert_eq!(normalizer.normalize(prefix), prefix, "{}", message);
+
+    match normalizer.quick_check(source) {
+        IsNormalized::Yes => assert!(normalized, "{}", message),
+        IsNormalized::No => assert!(!normalized, "{}", message),
+        IsNormalized::Maybe => (),
+    }
+}
@@ -0,0 +1,134 @@
+use unicode_composing::{
+    ComposingNormalizer, DecomposingNormalizer, FmtSink, IoSink, NormalizeSink,
+};
+use unicode_data::{NormalizationTest, NORMALIZATION_TESTS};
+
+/// запись в приёмники совпадает с результатом normalize
+macro_rules! test_sinks {
+    ($normalizer: expr, $source: expr, $message: expr) => {
+        let expected = $normalizer.normalize($source);
+
+        let mut string = String::from("prefix");
+        $normalizer.normalize_to($source, &mut string).unwrap();
+        assert_eq!(
+            string,
+            format!("prefix{}", expected),
+            "String, {}",
+            $message
+        );
+
+        let mut fmt = String::new();
+        $normalizer
+            .normalize_to($source, &mut FmtSink(&mut fmt))
+            .unwrap();
+        assert_eq!(fmt, expected, "fmt::Write, {}", $message);
+
+        let mut io = Vec::new();
+        $normalizer
+            .normalize_to($source, &mut IoSink(&mut io))
+            .unwrap();
+        assert_eq!(io, expected.as_bytes(), "io::Write, {}", $message);
+    };
+}
+
+/// тексты на разных языках
+#[test]
+fn sink_files()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+    let nfd = DecomposingNormalizer::new_nfd();
+    let nfkd = DecomposingNormalizer::new_nfkd();
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        let text = data.1.as_str();
+
+        test_sinks!(nfc, text, format!("nfc, {}", data.0));
+        test_sinks!(nfkc, text, format!("nfkc, {}", data.0));
+        test_sinks!(nfd, text, format!("nfd, {}", data.0));
+        test_sinks!(nfkd, text, format!("nfkd, {}", data.0));
+    }
+}
+
+/// строки из тестов UCD
+#[test]
+fn sink_ucd()
+{
+    let tests: &Vec<NormalizationTest> = &NORMALIZATION_TESTS;
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+
+    for t in tests {
+        for source in [&t.c1, &t.c2, &t.c3, &t.c4, &t.c5] {
+            test_sinks!(nfc, source, format!("nfc, {}", t.line));
+            test_sinks!(nfkc, source, format!("nfkc, {}", t.line));
+        }
+    }
+}
+
+/// ошибка записи возвращается из normalize_to
+#[test]
+fn sink_error()
+{
+    struct Failing;
+
+    impl std::io::Write for Failing
+    {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize>
+        {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()>
+        {
+            Ok(())
+        }
+    }
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let error = nfc
+        .normalize_to("a\u{301}b", &mut IoSink(Failing))
+        .unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::BrokenPipe);
+}
+
+/// после первой ошибки записи нормализация прекращается: приёмник больше не вызывается,
+/// возвращается первая ошибка
+#[test]
+fn sink_error_stops()
+{
+    struct Counting(usize);
+
+    impl NormalizeSink for Counting
+    {
+        type Error = usize;
+
+        fn write_str(&mut self, _: &str) -> Result<(), usize>
+        {
+            self.0 += 1;
+            Err(self.0)
+        }
+    }
+
+    let text = "e\u{301}\u{5D1}\u{5BC} \u{1100}\u{1161}".repeat(1000);
+
+    let mut sink = Counting(0);
+    assert_eq!(
+        ComposingNormalizer::new_nfc().normalize_to(&text, &mut sink),
+        Err(1)
+    );
+    assert_eq!(sink.0, 1);
+
+    let mut sink = Counting(0);
+    assert_eq!(
+        DecomposingNormalizer::new_nfd().normalize_to(&text, &mut sink),
+        Err(1)
+    );
+    assert_eq!(sink.0, 1);
+}
@@ -0,0 +1,103 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::{ComposingNormalizer, StreamingNormalizer};
+use unicode_data::{NormalizationTest, NORMALIZATION_TESTS};
+
+/// потоковая нормализация: строка подаётся частями по chunk_size символов
+fn normalize(normalizer: &ComposingNormalizer, source: &str, chunk_size: usize) -> String
+{
+    let mut stream = StreamingNormalizer::new(normalizer);
+    let mut result = String::new();
+
+    let chars: Vec<char> = source.chars().collect();
+
+    for chunk in chars.chunks(chunk_size) {
+        let chunk: String = chunk.iter().collect();
+        result.push_str(stream.push(&chunk));
+    }
+
+    stream.finish_into(&mut result);
+
+    result
+}
+
+/// потоковая нормализация совпадает с результатом ICU
+#[test]
+fn streaming_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let text = data.1.as_str();
+            let expected = icu.normalize(text);
+
+            for chunk_size in [1, 2, 3, 7, 64, 4096] {
+                assert_eq!(
+                    normalize(my, text, chunk_size),
+                    expected,
+                    "{}, {}, {}",
+                    form,
+                    data.0,
+                    chunk_size
+                );
+            }
+        }
+    }
+}
+
+/// тесты UCD: строки разделяются на части по одному и по два символа
+#[test]
+fn streaming_ucd()
+{
+    let tests: &Vec<NormalizationTest> = &NORMALIZATION_TESTS;
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+
+    for t in tests {
+        for chunk_size in [1, 2] {
+            for source in [&t.c1, &t.c2, &t.c3] {
+                assert_eq!(normalize(&nfc, source, chunk_size), t.c2, "nfc, {}", t.line);
+            }
+            for source in [&t.c4, &t.c5] {
+                assert_eq!(normalize(&nfc, source, chunk_size), t.c4, "nfc, {}", t.line);
+            }
+            for source in [&t.c1, &t.c2, &t.c3, &t.c4, &t.c5] {
+                assert_eq!(
+                    normalize(&nfkc, source, chunk_size),
+                    t.c4,
+                    "nfkc, {}",
+                    t.line
+                );
+            }
+        }
+    }
+}
+
+/// push_into не забирает из строки текст, записанный до начала потока
+#[test]
+fn streaming_keeps_prefix()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+
+    for (prefix, chunk) in [
+        ("\u{1100}", "\u{1161}"),
+        ("\u{B098}", "\u{11A8}"),
+        ("e", "\u{301}"),
+    ] {
+        let mut stream = StreamingNormalizer::new(&nfc);
+        let mut result = String::from(prefix);
+
+        stream.push_into(chunk, &mut result);
+        stream.finish_into(&mut result);
+
+        assert_eq!(result, format!("{}{}", prefix, chunk));
+    }
+}
@@ -0,0 +1,114 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::{ComposingNormalizer, UnpairedSurrogates};
+use unicode_data::{NormalizationTest, NORMALIZATION_TESTS};
+
+/// нормализация UTF-16 совпадает с результатом ICU
+#[test]
+fn utf16_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let units: Vec<u16> = data.1.encode_utf16().collect();
+            let expected = icu.normalize(data.1.as_str());
+
+            assert_eq!(
+                my.normalize_utf16(&units, UnpairedSurrogates::Error)
+                    .unwrap(),
+                expected.encode_utf16().collect::<Vec<u16>>(),
+                "{}, {}",
+                form,
+                data.0
+            );
+            assert_eq!(
+                my.normalize_utf16_to_string(&units, UnpairedSurrogates::Error)
+                    .unwrap(),
+                expected,
+                "{}, {}",
+                form,
+                data.0
+            );
+        }
+    }
+}
+
+/// тесты UCD для UTF-16
+#[test]
+fn utf16_ucd()
+{
+    let tests: &Vec<NormalizationTest> = &NORMALIZATION_TESTS;
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+
+    let normalize = |normalizer: &ComposingNormalizer, source: &String| -> String {
+        let units: Vec<u16> = source.encode_utf16().collect();
+        let result = normalizer
+            .normalize_utf16(&units, UnpairedSurrogates::Error)
+            .unwrap();
+
+        String::from_utf16(&result).unwrap()
+    };
+
+    for t in tests {
+        for source in [&t.c1, &t.c2, &t.c3] {
+            assert_eq!(normalize(&nfc, source), t.c2, "nfc, {}", t.line);
+        }
+        for source in [&t.c4, &t.c5] {
+            assert_eq!(normalize(&nfc, source), t.c4, "nfc, {}", t.line);
+        }
+        for source in [&t.c1, &t.c2, &t.c3, &t.c4, &t.c5] {
+            assert_eq!(normalize(&nfkc, source), t.c4, "nfkc, {}", t.line);
+        }
+    }
+}
+
+/// непарные суррогаты: ошибка, замена и сохранение
+#[test]
+fn utf16_unpaired_surrogates()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+
+    // A, непарный суррогат, U+030A, e, U+0301, непарный суррогат
+    let units = [0x41, 0xD800, 0x30A, 0x65, 0x301, 0xDC00];
+
+    let error = nfc
+        .normalize_utf16(&units, UnpairedSurrogates::Error)
+        .unwrap_err();
+
+    assert_eq!(error.valid_up_to(), 1);
+    assert_eq!(error.unit(), 0xD800);
+
+    assert_eq!(
+        nfc.normalize_utf16(&units, UnpairedSurrogates::Replace)
+            .unwrap(),
+        [0x41, 0xFFFD, 0x30A, 0xE9, 0xFFFD]
+    );
+    assert_eq!(
+        nfc.normalize_utf16(&units, UnpairedSurrogates::Preserve)
+            .unwrap(),
+        [0x41, 0xD800, 0x30A, 0xE9, 0xDC00]
+    );
+    assert_eq!(
+        nfc.normalize_utf16_to_string(&units, UnpairedSurrogates::Preserve)
+            .unwrap(),
+        "A\u{FFFD}\u{30A}\u{E9}\u{FFFD}"
+    );
+
+    // суррогатные пары: U+11099 и U+110BA комбинируются в U+1109A
+    let units = [0xD804, 0xDC99, 0xD804, 0xDCBA, 0x41, 0x30A];
+
+    assert_eq!(
+        nfc.normalize_utf16(&units, UnpairedSurrogates::Error)
+            .unwrap(),
+        [0xD804, 0xDC9A, 0xC5]
+    );
+}
@@ -0,0 +1,86 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::ComposingNormalizer;
+use unicode_data::{NormalizationTest, NORMALIZATION_TESTS};
+
+/// нормализация последовательностей кодпоинтов совпадает с результатом ICU
+#[test]
+fn utf32_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let chars: Vec<char> = data.1.chars().collect();
+            let codes: Vec<u32> = data.1.chars().map(u32::from).collect();
+            let expected: Vec<char> = icu.normalize(data.1.as_str()).chars().collect();
+
+            assert_eq!(my.normalize_chars(&chars), expected, "{}, {}", form, data.0);
+            assert_eq!(
+                my.normalize_code_points(&codes).unwrap(),
+                expected,
+                "{}, {}",
+                form,
+                data.0
+            );
+        }
+    }
+}
+
+/// тесты UCD для последовательностей символов
+#[test]
+fn utf32_ucd()
+{
+    let tests: &Vec<NormalizationTest> = &NORMALIZATION_TESTS;
+
+    let nfc = ComposingNormalizer::new_nfc();
+    let nfkc = ComposingNormalizer::new_nfkc();
+
+    let normalize = |normalizer: &ComposingNormalizer, source: &String| -> String {
+        let chars: Vec<char> = source.chars().collect();
+
+        normalizer.normalize_chars(&chars).into_iter().collect()
+    };
+
+    for t in tests {
+        for source in [&t.c1, &t.c2, &t.c3] {
+            assert_eq!(normalize(&nfc, source), t.c2, "nfc, {}", t.line);
+        }
+        for source in [&t.c4, &t.c5] {
+            assert_eq!(normalize(&nfc, source), t.c4, "nfc, {}", t.line);
+        }
+        for source in [&t.c1, &t.c2, &t.c3, &t.c4, &t.c5] {
+            assert_eq!(normalize(&nfkc, source), t.c4, "nfkc, {}", t.line);
+        }
+    }
+}
+
+/// суррогаты и значения вне диапазона Unicode
+#[test]
+fn utf32_invalid()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+
+    for (codes, valid_up_to, value) in [
+        (&[0x41, 0x30A, 0xD800][..], 2, 0xD800),
+        (&[0xDFFF], 0, 0xDFFF),
+        (&[0x10FFFF, 0x110000], 1, 0x110000),
+        (&[0x65, u32::MAX, 0x301], 1, u32::MAX),
+    ] {
+        let error = nfc.normalize_code_points(codes).unwrap_err();
+
+        assert_eq!(error.valid_up_to(), valid_up_to, "{:X?}", codes);
+        assert_eq!(error.value(), value, "{:X?}", codes);
+    }
+
+    assert_eq!(
+        nfc.normalize_code_points(&[0x41, 0x30A, 0x10FFFF]).unwrap(),
+        ['\u{C5}', '\u{10FFFF}']
+    );
+}
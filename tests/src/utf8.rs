@@ -0,0 +1,113 @@
+use icu_normalizer::ComposingNormalizer as icu;
+use unicode_composing::ComposingNormalizer;
+
+/// нормализация байтов совпадает с результатом ICU, в том числе при невалидных последовательностях
+#[test]
+fn utf8_icu()
+{
+    let forms = [
+        (ComposingNormalizer::new_nfc(), icu::new_nfc(), "nfc"),
+        (ComposingNormalizer::new_nfkc(), icu::new_nfkc(), "nfkc"),
+    ];
+
+    let files = crate::data::files()
+        .into_iter()
+        .chain(crate::data::decomposed_files());
+
+    for data in files {
+        for (my, icu, form) in forms.iter() {
+            let bytes = data.1.as_bytes();
+            let expected = icu.normalize(data.1.as_str());
+
+            assert_eq!(
+                my.normalize_utf8(bytes).unwrap(),
+                expected,
+                "{}, {}",
+                form,
+                data.0
+            );
+            assert_eq!(
+                my.normalize_utf8_lossy(bytes),
+                expected,
+                "{}, {}",
+                form,
+                data.0
+            );
+
+            // портим байты внутри текста
+            let mut broken = bytes.to_vec();
+
+            for i in (0 .. broken.len()).step_by(97) {
+                broken[i] = [0xFF, 0x80, 0xE2, 0xCC][i % 4];
+            }
+
+            let lossy = String::from_utf8_lossy(&broken);
+
+            assert_eq!(
+                my.normalize_utf8_lossy(&broken),
+                icu.normalize(&lossy),
+                "{}, {}",
+                form,
+                data.0
+            );
+        }
+    }
+}
+
+/// смещение и длина невалидной последовательности, замена наибольших частей
+#[test]
+fn utf8_invalid()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+
+    for (bytes, valid_up_to, error_len, lossy) in [
+        (&b"a\xCC\x81\xFF"[..], 3, 1, "\u{E1}\u{FFFD}"),
+        (b"\xE2\x82", 0, 2, "\u{FFFD}"),
+        (b"e\xE2\x82\xCC\x81", 1, 2, "e\u{FFFD}\u{301}"),
+        (b"\xF0\x80\x80", 0, 1, "\u{FFFD}\u{FFFD}\u{FFFD}"),
+        (b"\xED\xA0\x80a", 0, 1, "\u{FFFD}\u{FFFD}\u{FFFD}a"),
+        (b"A\xF4\x8F\xBF\xCC\x8A", 1, 3, "A\u{FFFD}\u{30A}"),
+    ] {
+        let error = nfc.normalize_utf8(bytes).unwrap_err();
+
+        assert_eq!(error.valid_up_to(), valid_up_to, "{:?}", bytes);
+        assert_eq!(error.error_len(), error_len, "{:?}", bytes);
+        assert_eq!(nfc.normalize_utf8_lossy(bytes), lossy, "{:?}", bytes);
+    }
+}
+
+/// смещение ошибки и нормализация на границах блоков, которыми проверяются байты
+#[test]
+fn utf8_blocks()
+{
+    let nfc = ComposingNormalizer::new_nfc();
+
+    for length in [4093, 4094, 4095, 4096, 4097, 8191] {
+        let prefix = "a".repeat(length);
+
+        // валидный текст: комбинирование через границу блока
+        let text = format!("{}e\u{301}\u{E0}\u{328}\u{1100}\u{1161}\u{11A8}", prefix);
+        assert_eq!(
+            nfc.normalize_utf8(text.as_bytes()).unwrap(),
+            nfc.normalize(&text),
+            "{}",
+            length
+        );
+
+        for (bytes, error_len) in [
+            (&b"\xE2\x82a"[..], 2),
+            (b"\xE2\x82", 2),
+            (b"\xF0\x9F\x98", 3),
+            (b"\xFF\xCC\x81", 1),
+            (b"\xED\xA0\x80", 1),
+        ] {
+            let mut input = prefix.as_bytes().to_vec();
+            input.extend_from_slice(bytes);
+
+            let error = nfc.normalize_utf8(&input).unwrap_err();
+
+            assert_eq!(error.valid_up_to(), length, "{}, {:?}", length, bytes);
+            assert_eq!(error.error_len(), error_len, "{}, {:?}", length, bytes);
+        }
+    }
+}
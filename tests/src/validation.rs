@@ -0,0 +1,313 @@
+use unicode_composing::{
+    ComposingNormalizer, CompositionData, DataError, DecomposingNormalizer, DecompositionData,
+    NormalizationData, MARKER_HANGUL_SYLLABLE,
+};
+
+/// индекс значения кодпоинта в основных данных (кодпоинт из непрерывной части таблиц)
+fn data_index(data: &DecompositionData, code: u32) -> usize
+{
+    assert!(code <= data.continuous_block_end);
+
+    let base = 0x600 | (((code >> 3) as u16) & !0xF);
+    let offset = (code as u16) & 0x7F;
+
+    (data.index[(base | (offset >> 3)) as usize] | offset & 0x7) as usize
+}
+
+/// запечённые данные проходят проверку
+#[test]
+fn validation_baked()
+{
+    let data = NormalizationData::baked();
+
+    assert_eq!(data.validate(), Ok(()));
+
+    let nfc =
+        ComposingNormalizer::try_from_baked(data.nfd, data.compositions, data.nfc_expansions, true)
+            .unwrap();
+    let nfkc = ComposingNormalizer::try_from_baked(
+        data.nfkd,
+        data.compositions,
+        data.nfkc_expansions,
+        false,
+    )
+    .unwrap();
+    let nfd = DecomposingNormalizer::try_from_baked(data.nfd, true).unwrap();
+    let nfkd = DecomposingNormalizer::try_from_baked(data.nfkd, false).unwrap();
+
+    for data in crate::data::files() {
+        let text = data.1.as_str();
+
+        assert_eq!(
+            nfc.normalize(text),
+            ComposingNormalizer::nfc().normalize(text)
+        );
+        assert_eq!(
+            nfkc.normalize(text),
+            ComposingNormalizer::nfkc().normalize(text)
+        );
+        assert_eq!(
+            nfd.normalize(text),
+            DecomposingNormalizer::nfd().normalize(text)
+        );
+        assert_eq!(
+            nfkd.normalize(text),
+            DecomposingNormalizer::nfkd().normalize(text)
+        );
+    }
+}
+
+/// повреждённые таблицы
+#[test]
+fn validation_errors()
+{
+    let baked = NormalizationData::baked();
+
+    // U+00C0 - пара A + U+0300, нестартер заменяем суррогатом
+    let mut data = baked.nfd.data.to_vec();
+    let index = data_index(&baked.nfd, 0xC0);
+
+    data[index] = (data[index] & 0xFFFF) | (0xD800 << 16);
+
+    let nfd = DecompositionData {
+        data: &data,
+        ..baked.nfd
+    };
+
+    let error = DecomposingNormalizer::try_from_baked(nfd, true)
+        .err()
+        .unwrap();
+
+    assert!(matches!(
+        error,
+        DataError::InvalidCodepoint { code: 0xC0, .. }
+    ));
+    assert!(error.to_string().contains("U+00C0"));
+
+    assert!(matches!(
+        ComposingNormalizer::try_from_baked(nfd, baked.compositions, baked.nfc_expansions, true),
+        Err(DataError::InvalidCodepoint { code: 0xC0, .. })
+    ));
+
+    // отметка слога хангыль у U+0041
+    let mut data = baked.nfd.data.to_vec();
+    let index = data_index(&baked.nfd, 0x41);
+
+    data[index] = (data[index] & !0xFE) | ((MARKER_HANGUL_SYLLABLE as u32) << 1);
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(
+            DecompositionData {
+                data: &data,
+                ..baked.nfd
+            },
+            true
+        ),
+        Err(DataError::InvalidCodepoint {
+            reason: "Hangul syllable marker outside of Hangul syllables",
+            ..
+        })
+    ));
+
+    // обрезанные данные
+    let nfd = DecompositionData {
+        data: &baked.nfd.data[.. baked.nfd.data.len() - 8],
+        ..baked.nfd
+    };
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(nfd, true),
+        Err(DataError::InvalidCodepoint {
+            reason: "data block out of range",
+            ..
+        })
+    ));
+
+    // обрезанный индекс
+    let nfd = DecompositionData {
+        index: &baked.nfd.index[.. 0x600],
+        ..baked.nfd
+    };
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(nfd, true),
+        Err(DataError::InvalidCodepoint { code: 0, .. })
+    ));
+
+    // обрезанные расширения
+    let nfkd = DecompositionData {
+        expansions: &baked.nfkd.expansions[.. baked.nfkd.expansions.len() / 2],
+        ..baked.nfkd
+    };
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(nfkd, false),
+        Err(DataError::InvalidCodepoint { .. })
+    ));
+
+    // блок U+0900 ..= U+093F (деванагари, первый нестартер - нукта U+093C) отмечен как пропускаемый NFC
+    let mut quick_check = baked.nfd.quick_check.to_vec();
+
+    quick_check[32] |= 1 << 0x24;
+
+    let nfd = DecompositionData {
+        quick_check: &quick_check,
+        ..baked.nfd
+    };
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(nfd, true),
+        Err(DataError::InvalidCodepoint { code: 0x93C, .. })
+    ));
+
+    // U+0300 (нестартер, 2-байтовая последовательность CC 80) отмечен как стартер без декомпозиции NFD
+    let mut quick_check = baked.nfd.quick_check.to_vec();
+
+    quick_check[64 + 0x0C] |= 1;
+
+    let nfd = DecompositionData {
+        quick_check: &quick_check,
+        ..baked.nfd
+    };
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(nfd, true),
+        Err(DataError::InvalidCodepoint { code: 0x300, .. })
+    ));
+
+    // 4-байтовые последовательности с первым байтом F0 отмечены как проходящие проверку
+    let mut quick_check = baked.nfd.quick_check.to_vec();
+
+    quick_check[0x30] = u64::MAX;
+
+    let nfd = DecompositionData {
+        quick_check: &quick_check,
+        ..baked.nfd
+    };
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(nfd, true),
+        Err(DataError::InvalidCodepoint {
+            reason: "quick check table passes 4-byte sequences",
+            ..
+        })
+    ));
+
+    // строка 3-байтовых последовательностей U+0900 ..= U+093F пропускает нукту U+093C в NFC
+    let mut quick_check_index = baked.nfd.quick_check_index.to_vec();
+    let mut quick_check_rows = baked.nfd.quick_check_rows.to_vec();
+
+    quick_check_rows.push(u64::MAX);
+    quick_check_index[0x900 >> 6] = (quick_check_rows.len() - 1) as u16;
+
+    let nfd = DecompositionData {
+        quick_check_index: &quick_check_index,
+        quick_check_rows: &quick_check_rows,
+        ..baked.nfd
+    };
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(nfd, true),
+        Err(DataError::InvalidCodepoint { code: 0x93C, .. })
+    ));
+
+    // номер строки за пределами таблицы строк
+    let mut quick_check_index = baked.nfd.quick_check_index.to_vec();
+
+    quick_check_index[1024 + (0x4E00 >> 6)] = baked.nfd.quick_check_rows.len() as u16;
+
+    let nfd = DecompositionData {
+        quick_check_index: &quick_check_index,
+        ..baked.nfd
+    };
+
+    assert_eq!(
+        DecomposingNormalizer::try_from_baked(nfd, true).map(|_| ()),
+        Err(DataError::InvalidCodepoint {
+            code: 0x4E00,
+            reason: "quick check row out of range"
+        })
+    );
+
+    // обрезанная таблица быстрой проверки
+    let nfd = DecompositionData {
+        quick_check: &baked.nfd.quick_check[.. 64],
+        ..baked.nfd
+    };
+
+    assert!(matches!(
+        DecomposingNormalizer::try_from_baked(nfd, true),
+        Err(DataError::InvalidCodepoint { .. })
+    ));
+
+    // отсутствующая замена расширений NFKC
+    assert!(matches!(
+        ComposingNormalizer::try_from_baked(
+            baked.nfkd,
+            baked.compositions,
+            baked.nfc_expansions,
+            false
+        ),
+        Err(DataError::InvalidCodepoint { .. })
+    ));
+
+    // композиция в суррогат
+    let mut compositions = baked.compositions.compositions.to_vec();
+
+    compositions[0] = (compositions[0] & !(0x3FFFF << 18)) | (0xD800 << 18);
+
+    let error = ComposingNormalizer::try_from_baked(
+        baked.nfd,
+        CompositionData {
+            compositions: &compositions,
+            ..baked.compositions
+        },
+        baked.nfc_expansions,
+        true,
+    )
+    .err()
+    .unwrap();
+
+    assert!(matches!(
+        error,
+        DataError::InvalidComposition { index: 0, .. }
+    ));
+
+    // обрезанная таблица композиций
+    let compositions = CompositionData {
+        compositions: &baked.compositions.compositions[.. 1],
+        ..baked.compositions
+    };
+
+    assert!(matches!(
+        ComposingNormalizer::try_from_baked(baked.nfd, compositions, baked.nfc_expansions, true),
+        Err(DataError::InvalidCodepoint { .. })
+    ));
+
+    // ячейки хеша композиций не указывают на записи
+    let slots = vec![0; baked.compositions.slots.len()];
+
+    let compositions = CompositionData {
+        slots: &slots,
+        ..baked.compositions
+    };
+
+    assert!(matches!(
+        ComposingNormalizer::try_from_baked(baked.nfd, compositions, baked.nfc_expansions, true),
+        Err(DataError::InvalidCodepoint {
+            reason: "composition is not reachable by hash",
+            ..
+        })
+    ));
+
+    // пустая таблица смещений хеша композиций
+    let compositions = CompositionData {
+        displacements: &[],
+        ..baked.compositions
+    };
+
+    assert!(matches!(
+        ComposingNormalizer::try_from_baked(baked.nfd, compositions, baked.nfc_expansions, true),
+        Err(DataError::InvalidCodepoint { .. })
+    ));
+}
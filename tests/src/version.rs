@@ -0,0 +1,119 @@
+use unicode_composing::{ComposingNormalizer, DataError, DecomposingNormalizer, UnicodeVersion};
+
+/// версия Unicode запечённых данных
+#[test]
+fn version_baked()
+{
+    assert_eq!(
+        ComposingNormalizer::nfc().unicode_version(),
+        UnicodeVersion::V15_1
+    );
+    assert_eq!(
+        ComposingNormalizer::nfkc().unicode_version(),
+        UnicodeVersion::V15_1
+    );
+    assert_eq!(
+        DecomposingNormalizer::nfd().unicode_version(),
+        UnicodeVersion::V15_1
+    );
+    assert_eq!(
+        DecomposingNormalizer::nfkd().unicode_version(),
+        UnicodeVersion::V15_1
+    );
+
+    let nfc = ComposingNormalizer::new_nfc_with(UnicodeVersion::V15_1).unwrap();
+
+    for data in crate::data::files() {
+        let text = data.1.as_str();
+
+        assert_eq!(
+            nfc.normalize(text),
+            ComposingNormalizer::nfc().normalize(text)
+        );
+    }
+
+    assert_eq!(
+        ComposingNormalizer::new_nfc_with(UnicodeVersion::new(16, 0, 0)).err(),
+        Some(DataError::UnsupportedUnicodeVersion(UnicodeVersion::new(
+            16, 0, 0
+        )))
+    );
+}
+
+/// без возможности unicode_14_0 данные Unicode 14.0 не запекаются
+#[cfg(not(feature = "unicode_14_0"))]
+#[test]
+fn version_14_0_disabled()
+{
+    assert_eq!(
+        ComposingNormalizer::new_nfc_with(UnicodeVersion::V14_0).err(),
+        Some(DataError::UnsupportedUnicodeVersion(UnicodeVersion::V14_0))
+    );
+    assert_eq!(
+        DecomposingNormalizer::new_nfd_with(UnicodeVersion::V14_0).err(),
+        Some(DataError::UnsupportedUnicodeVersion(UnicodeVersion::V14_0))
+    );
+}
+
+/// данные Unicode 14.0: кодпоинты, добавленные позже, не нормализуются
+#[cfg(feature = "unicode_14_0")]
+#[test]
+fn version_14_0()
+{
+    let nfc = ComposingNormalizer::new_nfc_with(UnicodeVersion::V14_0).unwrap();
+    let nfkc = ComposingNormalizer::new_nfkc_with(UnicodeVersion::V14_0).unwrap();
+    let nfd = DecomposingNormalizer::new_nfd_with(UnicodeVersion::V14_0).unwrap();
+    let nfkd = DecomposingNormalizer::new_nfkd_with(UnicodeVersion::V14_0).unwrap();
+
+    assert_eq!(
+        unicode_composing::NormalizationData::baked_with(UnicodeVersion::V14_0)
+            .unwrap()
+            .validate(),
+        Ok(())
+    );
+
+    for normalizer in [&nfc, &nfkc] {
+        assert_eq!(normalizer.unicode_version(), UnicodeVersion::V14_0);
+    }
+
+    for normalizer in [&nfd, &nfkd] {
+        assert_eq!(normalizer.unicode_version(), UnicodeVersion::V14_0);
+    }
+
+    // кодпоинты, назначенные до 14.0, нормализуются одинаково
+    for data in crate::data::files() {
+        let text = data.1.as_str();
+
+        assert_eq!(
+            nfc.normalize(text),
+            ComposingNormalizer::nfc().normalize(text)
+        );
+        assert_eq!(
+            nfkc.normalize(text),
+            ComposingNormalizer::nfkc().normalize(text)
+        );
+        assert_eq!(
+            nfd.normalize(text),
+            DecomposingNormalizer::nfd().normalize(text)
+        );
+        assert_eq!(
+            nfkd.normalize(text),
+            DecomposingNormalizer::nfkd().normalize(text)
+        );
+    }
+
+    // U+1E030 MODIFIER LETTER CYRILLIC SMALL A (Unicode 15.0) - <super> U+0430
+    assert_eq!(nfkc.normalize("\u{1E030}"), "\u{1E030}");
+    assert_eq!(
+        ComposingNormalizer::nfkc().normalize("\u{1E030}"),
+        "\u{430}"
+    );
+
+    // U+10EFD ARABIC SMALL LOW WORD SAKTA (Unicode 15.0, CCC 220) - до 15.0 не назначен (стартер),
+    // поэтому в 14.0 не переставляется с предшествующим U+0301 (CCC 230)
+    assert_eq!(nfd.normalize("a\u{301}\u{10EFD}"), "a\u{301}\u{10EFD}");
+    assert_eq!(
+        DecomposingNormalizer::nfd().normalize("a\u{301}\u{10EFD}"),
+        "a\u{10EFD}\u{301}"
+    );
+}
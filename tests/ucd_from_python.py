@@ -0,0 +1,65 @@
+# ТОЛЬКО ДЛЯ ТЕСТОВ: не используйте эти файлы для запекания данных, поставляемых с крейтом.
+#
+# восстановление файлов UCD, которые читает bakery, по модулю unicodedata Python
+# (версия Unicode - unicodedata.unidata_version, для Python 3.11 - 14.0.0).
+# в UnicodeData.txt пишутся только CCC и декомпозиция, остальные поля - заглушки, поэтому результат -
+# не UCD, а независимый источник для сверки: данные, запечённые из официальных файлов UCD, должны
+# совпадать с запечёнными из этих файлов. запуск: python3.11 ucd_from_python.py <каталог>
+
+import sys
+import unicodedata as u
+
+out = sys.argv[1]
+version = u.unidata_version
+
+ud = []
+fce = set()
+excl = set()
+seconds = set()
+
+
+def canonical(c):
+    d = u.decomposition(chr(c))
+    if not d or d.startswith('<'):
+        return None
+    return [int(x, 16) for x in d.split()]
+
+
+for c in range(0x110000):
+    if 0xD800 <= c <= 0xDFFF:
+        continue
+    ch = chr(c)
+    cc = u.combining(ch)
+    d = u.decomposition(ch)
+    if cc or d:
+        ud.append(f"{c:04X};X;Mn;{cc};NSM;{d};;;;N;;;;;")
+    m = canonical(c)
+    if m is not None and u.normalize('NFC', ch) != ch:
+        fce.add(c)
+        if len(m) == 2 and cc == 0 and u.combining(chr(m[0])) == 0:
+            excl.add(c)
+    if m is not None and len(m) == 2 and u.normalize('NFC', ch) == ch:
+        seconds.add(m[1])
+
+# гласные и завершающие согласные хангыль комбинируются алгоритмически
+seconds |= set(range(0x1161, 0x1176)) | set(range(0x11A8, 0x11C3))
+
+open(out + '/UnicodeData.txt', 'w').write("\n".join(ud) + "\n")
+open(out + '/CompositionExclusions.txt', 'w').write(
+    f"# CompositionExclusions-{version}.txt\n\n" + "".join(f"{c:04X}  # X\n" for c in sorted(excl))
+)
+
+lines = [f"# DerivedNormalizationProps-{version}.txt", ""]
+lines += [f"{c:04X}          ; Full_Composition_Exclusion # X" for c in sorted(fce)]
+
+for name, form in (("NFC_QC", "NFC"), ("NFKC_QC", "NFKC")):
+    for c in range(0x110000):
+        if 0xD800 <= c <= 0xDFFF:
+            continue
+        ch = chr(c)
+        if u.normalize(form, ch) != ch:
+            lines.append(f"{c:04X}          ; {name}; N # X")
+        elif c in seconds:
+            lines.append(f"{c:04X}          ; {name}; M # X")
+
+open(out + '/DerivedNormalizationProps.txt', 'w').write("\n".join(lines) + "\n")